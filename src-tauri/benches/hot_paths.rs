@@ -0,0 +1,109 @@
+// Timings of the paths every PSO iteration goes through, at several problem sizes:
+//
+//   cargo bench --bench hot_paths
+//
+// Sizes are cut from, or repeated from, data/course.csv so the mix of lecturers, classes
+// and SKS stays the same as in real input.
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use schedule_optimization_lib::algorithms::models::{CourseRequest, LecturerPreference, OptimizedCourse, PsoParameters, PSO};
+use schedule_optimization_lib::algorithms::preference::{parse_csv, parse_preference_csv};
+use schedule_optimization_lib::algorithms::tune::suggest_parameters;
+
+const SIZES: [usize; 4] = [50, 150, 300, 600];
+// Finer around the serial/parallel crossover of detect_conflicts
+const CONFLICT_SIZES: [usize; 8] = [50, 100, 150, 200, 300, 450, 600, 1200];
+
+fn input() -> (Vec<CourseRequest>, Vec<LecturerPreference>) {
+    let courses = parse_csv(include_str!("../data/course.csv"), "Course CSV").unwrap();
+    let prefs = parse_preference_csv(include_str!("../data/preference.csv")).unwrap();
+    (courses, prefs)
+}
+
+// `size` courses; every copy past the sample data becomes a new set of classes
+fn courses(sample: &[CourseRequest], size: usize) -> Vec<CourseRequest> {
+    (0..size)
+        .map(|i| {
+            let copy = (i / sample.len()) as u32;
+            let mut course = sample[i % sample.len()].clone();
+            course.id_jadwal += copy * 100_000;
+            course.id_kelas += copy * 100;
+            course
+        })
+        .collect()
+}
+
+fn params() -> PsoParameters {
+    PsoParameters { seed: Some(42), ..suggest_parameters(0, 1, Default::default(), Default::default()) }
+}
+
+fn random_position(dimension: usize) -> Vec<f64> {
+    let mut rng = StdRng::seed_from_u64(42);
+    (0..dimension).map(|_| rng.random_range(0.0..1.0)).collect()
+}
+
+// Problem of `size` courses with a decoded random schedule to check
+fn setup(sample: &[CourseRequest], prefs: &[LecturerPreference], size: usize) -> (PSO, Vec<f64>, Vec<OptimizedCourse>) {
+    let pso = PSO::new(courses(sample, size), prefs.to_vec(), params());
+    let position = random_position(pso.courses.len() * 2);
+    let schedule = PSO::position_to_schedule(&position, &pso.courses, &pso.parameters.constraints);
+    (pso, position, schedule)
+}
+
+fn position_to_schedule(c: &mut Criterion) {
+    let (sample, prefs) = input();
+    let mut group = c.benchmark_group("position_to_schedule");
+    for size in SIZES {
+        let (pso, position, _) = setup(&sample, &prefs, size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &position, |b, position| {
+            b.iter(|| PSO::position_to_schedule(black_box(position), &pso.courses, &pso.parameters.constraints))
+        });
+    }
+    group.finish();
+}
+
+// The serial and the parallel bucket search side by side, to place
+// ScheduleChecker::parallel_conflicts_from where the parallel one starts to win
+fn detect_conflicts(c: &mut Criterion) {
+    let (sample, prefs) = input();
+    let mut group = c.benchmark_group("detect_conflicts");
+    for size in CONFLICT_SIZES {
+        let (mut pso, _, schedule) = setup(&sample, &prefs, size);
+        for (path, parallel_from) in [("serial", usize::MAX), ("parallel", 0)] {
+            pso.checker.parallel_conflicts_from = parallel_from;
+            group.bench_with_input(BenchmarkId::new(path, size), &schedule, |b, schedule| {
+                b.iter(|| pso.checker.detect_conflicts(black_box(schedule)))
+            });
+        }
+    }
+    group.finish();
+}
+
+fn check_preferences(c: &mut Criterion) {
+    let (sample, prefs) = input();
+    let mut group = c.benchmark_group("check_preferences");
+    for size in SIZES {
+        let (pso, _, schedule) = setup(&sample, &prefs, size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &schedule, |b, schedule| {
+            b.iter(|| pso.checker.check_preferences(black_box(schedule)))
+        });
+    }
+    group.finish();
+}
+
+// Evaluation, global best and particle moves for the whole swarm, as in optimize()
+fn iteration(c: &mut Criterion) {
+    let (sample, prefs) = input();
+    let mut group = c.benchmark_group("iteration");
+    group.sample_size(20);
+    for size in SIZES {
+        let (mut pso, _, _) = setup(&sample, &prefs, size);
+        pso.start(0);
+        group.bench_function(BenchmarkId::from_parameter(size), |b| b.iter(|| pso.step()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, position_to_schedule, detect_conflicts, check_preferences, iteration);
+criterion_main!(benches);
@@ -0,0 +1,259 @@
+// Several departments optimized in one invocation, each from its own course/preference files.
+// Shared by the `batch` binary and the optimize_batch command.
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{atomic::{AtomicBool, Ordering}, Arc};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use super::error::ScheduleError;
+use super::export::write_schedule;
+use super::models::{ConstraintConfig, PenaltyWeights, PsoParameters, ScheduleChecker, PSO};
+use super::preference::read_input;
+use super::tune::suggest_parameters;
+
+/// Combined summary written next to the per-department schedules
+pub const SUMMARY_FILE: &str = "ringkasan.csv";
+
+/// JSON manifest listing the departments. Relative file paths are resolved against the
+/// folder of the manifest.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchManifest {
+    pub departments: Vec<Department>,
+    #[serde(default)]
+    pub params: Option<PsoParameters>,  // For departments without their own; empty = suggest_parameters
+    // With suggested parameters, for departments without their own
+    #[serde(default)]
+    pub constraints: Option<ConstraintConfig>,
+    #[serde(default)]
+    pub penalty_weights: Option<PenaltyWeights>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Department {
+    pub name: String,  // Also names the output file
+    pub course_csv: PathBuf,
+    pub preference_csv: PathBuf,
+    #[serde(default)]
+    pub lecturer_csv: Option<PathBuf>,
+    #[serde(default)]
+    pub absence_csv: Option<PathBuf>,
+    #[serde(default)]
+    pub params: Option<PsoParameters>,
+    // Kept on top of the suggested parameters when there are no params
+    #[serde(default)]
+    pub constraints: Option<ConstraintConfig>,
+    #[serde(default)]
+    pub penalty_weights: Option<PenaltyWeights>,
+}
+
+/// Outcome of one department, one row of the combined summary
+#[derive(Debug, Clone, Serialize)]
+pub struct DepartmentSummary {
+    pub name: String,
+    pub success: bool,
+    pub courses: usize,
+    pub fitness: Option<f64>,
+    pub is_feasible: bool,
+    pub hard_violations: u32,
+    pub soft_penalty: f64,
+    pub durasi: f64,  // Detik
+    pub output: Option<String>,
+    pub message: Option<String>,  // Why the department failed
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchSummary {
+    pub departments: Vec<DepartmentSummary>,
+    pub feasible: usize,
+    pub failed: usize,
+    pub durasi: f64,
+    pub stopped_early: bool,  // Departments not started before the stop are missing
+    pub summary: String,      // Path of the combined summary CSV
+}
+
+impl BatchManifest {
+    pub fn load(path: &Path) -> Result<Self, ScheduleError> {
+        let json = fs::read_to_string(path).map_err(|e| format!("Gagal membaca file {}: {}", path.display(), e))?;
+        let mut manifest: Self = serde_json::from_str(&json)
+            .map_err(|e| ScheduleError::invalid(format!("Manifest {} tidak valid: {}", path.display(), e)))?;
+
+        let base = path.parent().unwrap_or(Path::new(""));
+        for department in &mut manifest.departments {
+            let files = [&mut department.course_csv, &mut department.preference_csv]
+                .into_iter()
+                .chain(department.lecturer_csv.as_mut())
+                .chain(department.absence_csv.as_mut());
+            for file in files {
+                *file = base.join(&*file);
+            }
+        }
+
+        manifest.check()?;
+        Ok(manifest)
+    }
+
+    // Every department needs its own output file
+    fn check(&self) -> Result<(), ScheduleError> {
+        if self.departments.is_empty() {
+            return Err(ScheduleError::invalid("Manifest tidak berisi departemen"));
+        }
+        let mut names = HashSet::new();
+        for department in &self.departments {
+            if !names.insert(file_stem(&department.name)) {
+                return Err(ScheduleError::invalid(format!("Nama departemen ganda: {}", department.name)));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl DepartmentSummary {
+    fn failed(name: &str, message: String) -> Self {
+        DepartmentSummary {
+            name: name.to_string(),
+            success: false,
+            courses: 0,
+            fitness: None,
+            is_feasible: false,
+            hard_violations: 0,
+            soft_penalty: 0.0,
+            durasi: 0.0,
+            output: None,
+            message: Some(message),
+        }
+    }
+}
+
+/// Optimizes every department of the manifest, `parallel` at a time, writing
+/// `<name>.<format>` per department and the combined summary into `out_dir`.
+/// A department that fails is reported in the summary, the others still run.
+pub async fn run_batch(
+    manifest: BatchManifest,
+    out_dir: &Path,
+    format: &str,
+    parallel: usize,
+    stop_flag: Arc<AtomicBool>,
+    mut on_done: impl FnMut(&DepartmentSummary),
+) -> Result<BatchSummary, ScheduleError> {
+    fs::create_dir_all(out_dir).map_err(|e| format!("Gagal membuat folder {}: {}", out_dir.display(), e))?;
+    let started = Instant::now();
+
+    let default_params = manifest.params;
+    let (default_constraints, default_weights) = (manifest.constraints, manifest.penalty_weights);
+    let mut pending = manifest.departments.into_iter().enumerate();
+    let mut running = tokio::task::JoinSet::new();
+    let mut names = HashMap::new();  // Task id -> manifest index and department name
+    let mut finished = Vec::new();
+
+    loop {
+        while running.len() < parallel.max(1) && !stop_flag.load(Ordering::Relaxed) {
+            let Some((index, mut department)) = pending.next() else { break };
+            let output = out_dir.join(format!("{}.{}", file_stem(&department.name), format));
+            let params = department.params.clone().or_else(|| default_params.clone());
+            department.constraints = department.constraints.or_else(|| default_constraints.clone());
+            department.penalty_weights = department.penalty_weights.or_else(|| default_weights.clone());
+            let stop_flag = stop_flag.clone();
+            let runtime = tokio::runtime::Handle::current();
+            let name = department.name.clone();
+            let task = running.spawn_blocking(move || {
+                let department_start = Instant::now();
+                let mut summary = runtime
+                    .block_on(optimize_department(&department, params, &output, stop_flag))
+                    .unwrap_or_else(|e| DepartmentSummary::failed(&department.name, e.to_string()));
+                summary.durasi = department_start.elapsed().as_secs_f64();
+                (index, summary)
+            });
+            names.insert(task.id(), (index, name));
+        }
+
+        let (index, summary) = match running.join_next_with_id().await {
+            Some(Ok((id, done))) => {
+                names.remove(&id);
+                done
+            }
+            // A panicking department still gets its row, found again by its task id
+            Some(Err(e)) => {
+                let (index, name) = names.remove(&e.id()).unwrap_or_default();
+                (index, DepartmentSummary::failed(&name, format!("Optimasi departemen gagal: {}", e)))
+            }
+            None => break,
+        };
+        on_done(&summary);
+        finished.push((index, summary));
+    }
+
+    // Manifest order, not the order they happened to finish in
+    finished.sort_by_key(|(index, _)| *index);
+    let departments: Vec<DepartmentSummary> = finished.into_iter().map(|(_, summary)| summary).collect();
+
+    let summary_path = out_dir.join(SUMMARY_FILE);
+    let mut writer = csv::Writer::from_path(&summary_path).map_err(|e| format!("Gagal menulis {}: {}", summary_path.display(), e))?;
+    for department in &departments {
+        writer.serialize(department).map_err(|e| e.to_string())?;
+    }
+    writer.flush().map_err(|e| format!("Gagal menulis {}: {}", summary_path.display(), e))?;
+
+    Ok(BatchSummary {
+        feasible: departments.iter().filter(|d| d.is_feasible).count(),
+        failed: departments.iter().filter(|d| !d.success).count(),
+        durasi: started.elapsed().as_secs_f64(),
+        stopped_early: stop_flag.load(Ordering::Relaxed),
+        summary: summary_path.display().to_string(),
+        departments,
+    })
+}
+
+// Best of `num_runs` runs, evaluated and written to `output`
+async fn optimize_department(
+    department: &Department,
+    params: Option<PsoParameters>,
+    output: &Path,
+    stop_flag: Arc<AtomicBool>,
+) -> Result<DepartmentSummary, ScheduleError> {
+    let (courses, time_preferences) = read_input(
+        &department.course_csv,
+        &department.preference_csv,
+        department.lecturer_csv.as_deref(),
+        department.absence_csv.as_deref(),
+    )?;
+    let params = params.unwrap_or_else(|| {
+        let lecturers: HashSet<u32> = courses.iter().map(|c| c.id_dosen).collect();
+        suggest_parameters(
+            courses.len(),
+            lecturers.len(),
+            department.constraints.clone().unwrap_or_default(),
+            department.penalty_weights.clone().unwrap_or_default(),
+        )
+    });
+    if params.swarm_size <= 0 || params.max_iterations == 0 {
+        return Err(ScheduleError::invalid("swarm_size dan max_iterations harus lebih dari 0"));
+    }
+
+    let course_count = courses.len();
+    let (schedule, fitness) = PSO::optimize_best(courses, time_preferences.clone(), &params, stop_flag, |_| {}).await?;
+
+    let checker = ScheduleChecker::new(time_preferences, params.constraints.clone(), params.penalty_weights.clone());
+    let evaluation = checker.evaluate(&schedule);
+    write_schedule(&schedule, output)?;
+
+    Ok(DepartmentSummary {
+        name: department.name.clone(),
+        success: true,
+        courses: course_count,
+        fitness: Some(fitness),
+        is_feasible: evaluation.is_feasible(),
+        hard_violations: evaluation.hard_violations,
+        soft_penalty: evaluation.soft_penalty,
+        durasi: 0.0,
+        output: Some(output.display().to_string()),
+        message: None,
+    })
+}
+
+// Department name usable as a file name
+fn file_stem(name: &str) -> String {
+    name.chars().map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' }).collect()
+}
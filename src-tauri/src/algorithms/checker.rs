@@ -1,147 +1,1701 @@
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+// The one place schedules are evaluated: the PSO loop and every reporting path go through ScheduleChecker
+
+use std::{collections::{BTreeMap, HashMap, HashSet}, hash::Hash, sync::Arc};
+
+use chrono::{Datelike, Duration, NaiveDate};
+
+use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
 use serde::Serialize;
 
-use super::models::{OptimizedCourse, ScheduleChecker, TimePreferenceRequest };
+use super::models::{
+    BackToBack, ClassKey, ConstraintConfig, Evaluation, LecturerPreference, OptimizedCourse, PenaltyWeights, PreferenceLevel, ScheduleEdit,
+    LecturerSatisfaction, MovedCourse, PenaltyChange, PreferenceSatisfaction, SatisfactionChange, ScheduleComparison, RoomUsage, ScheduleChecker, SemesterCalendar, TimeRange,
+    DEFAULT_HARI_AKTIF, DEFAULT_MAX_SKS_KELAS_4_PERTEMUAN, DEFAULT_MAX_SKS_KELAS_PER_HARI, DEFAULT_WAKTU, ID_WAKTU_MALAM,
+};
 
 #[derive(Serialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 pub struct ConflictMessage {
     jadwal_a: u32,
     jadwal_b: u32,
+    jenis: ConflictType,
+    severity: Severity,
+    penalty: u32,
     deskripsi: String,
 }
 
+/// Machine-readable conflict type, serialized as "dosen", "kelas", "ruangan", "kohort",
+/// "berurutan" or "jeda"
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictType {
+    Dosen,
+    Kelas,
+    Ruangan,
+    Kohort,
+    Berurutan,  // Against the lecturer's back-to-back preference, soft
+    Jeda,       // Shorter break than jeda_min_dosen between a lecturer's sessions, soft
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Hard,     // Membuat jadwal tidak layak
+    Soft,     // Dihitung sebagai penalti lunak
+    Warning,  // Hanya dilaporkan, bobotnya 0
+}
+
 #[derive(Serialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 pub struct ConflictResult {
     penalty: u32,
     conflicts: Vec<ConflictMessage>,
 }
 
 #[derive(Serialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 pub struct PreferenceMessage {
     pub id_jadwal: u32,
     pub id_dosen: u32,
     pub hari: u32,
     pub jam_mulai: u32,
+    pub penalty: u32,  // Sudah dikalikan prioritas dosen
     pub deskripsi: String,
 }
 
 #[derive(Serialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 pub struct PreferenceResult {
     pub penalty: u32,
     pub violations: Vec<PreferenceMessage>,
 }
 
+#[derive(Serialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct ConstraintMessage {
+    pub id_jadwal: Vec<u32>,
+    pub deskripsi: String,
+}
+
+#[derive(Serialize, Default)]
+pub struct ConstraintResult {
+    pub penalty: u32,
+    pub violations: Vec<ConstraintMessage>,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ConstraintKind {
+    Hard,  // Harus terpenuhi agar jadwal layak
+    Soft,  // Preferensi yang boleh dikompromikan
+}
+
+pub type Violation = ConstraintMessage;
+
+/// A rule evaluated alongside the built-in checks, registered with `ScheduleChecker::register`
+pub trait Constraint: Send + Sync {
+    /// Key of the rule in the penalty breakdown
+    fn name(&self) -> &'static str;
+
+    /// Hard rules make a schedule infeasible when violated
+    fn kind(&self) -> ConstraintKind {
+        ConstraintKind::Soft
+    }
+
+    fn penalty(&self, schedule: &[OptimizedCourse]) -> (u32, Vec<Violation>);
+}
+
+impl std::fmt::Debug for dyn Constraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Outcome of `ScheduleChecker::try_edit`; `selisih` is negative when the edit improves the schedule
+#[derive(Serialize)]
+pub struct EditImpact {
+    pub fitness_sebelum: f64,
+    pub fitness_sesudah: f64,
+    pub selisih: f64,
+    pub is_feasible: bool,
+    pub konflik_baru: Vec<ConflictMessage>,
+    pub konflik_hilang: Vec<ConflictMessage>,
+    pub schedule: Vec<OptimizedCourse>,
+}
+
+#[derive(Serialize, Default)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct EvaluationReport {
+    pub is_feasible: bool,
+    pub conflicts: Vec<ConflictMessage>,
+    pub preference_violations: Vec<PreferenceMessage>,
+    pub hard_violations: Vec<ConstraintMessage>,
+    pub soft_violations: Vec<ConstraintMessage>,
+}
+
+impl Evaluation {
+    pub fn fitness(&self) -> f64 {
+        self.hard_penalty + self.soft_penalty
+    }
+
+    pub fn is_feasible(&self) -> bool {
+        self.hard_violations == 0
+    }
+
+    /// Score in (0, 1], 1 for a schedule without penalties and 0.5 when the penalty
+    /// averages one default-weight violation (100) per course
+    pub fn normalized(&self, course_count: usize) -> f64 {
+        1.0 / (1.0 + self.fitness() / (course_count.max(1) as f64 * 100.0))
+    }
+}
+
+impl std::ops::Add for Evaluation {
+    type Output = Evaluation;
+
+    fn add(self, other: Evaluation) -> Evaluation {
+        Evaluation {
+            hard_violations: self.hard_violations + other.hard_violations,
+            hard_penalty: self.hard_penalty + other.hard_penalty,
+            soft_penalty: self.soft_penalty + other.soft_penalty,
+        }
+    }
+}
+
+pub fn nama_hari(hari: u32) -> &'static str {
+    match hari {
+        1 => "Senin",
+        2 => "Selasa",
+        3 => "Rabu",
+        4 => "Kamis",
+        5 => "Jumat",
+        6 => "Sabtu",
+        7 => "Minggu",
+        _ => "Hari Tidak Dikenal",
+    }
+}
+
+// Dates a weekly course meets on during the semester
+pub(crate) fn meeting_dates(kalender: &SemesterCalendar, hari: u32) -> impl Iterator<Item = NaiveDate> {
+    let start = kalender.tanggal_mulai;
+    let monday = start - Duration::days(start.weekday().num_days_from_monday() as i64);
+
+    (0..kalender.jumlah_minggu as i64)
+        .map(move |week| monday + Duration::days(week * 7 + hari as i64 - 1))
+        .filter(move |date| *date >= start)
+}
+
+// Minutes since midnight as HH:MM
+pub(crate) fn format_jam(menit: u32) -> String {
+    format!("{:02}:{:02}", menit / 60, menit % 60)
+}
+
+// Meetings per id_jadwal; a course split by batas_pecah_sks has two sharing the id
+fn meetings_by_id(schedule: &[OptimizedCourse]) -> HashMap<u32, Vec<&OptimizedCourse>> {
+    let mut by_id: HashMap<u32, Vec<&OptimizedCourse>> = HashMap::new();
+    for course in schedule {
+        by_id.entry(course.id_jadwal).or_default().push(course);
+    }
+    by_id
+}
+
+// Below this many courses the buckets are too small for rayon to pay for its scheduling.
+// Particles are already evaluated in parallel, so this mostly helps one-off evaluations.
+// From `cargo bench --bench hot_paths -- detect_conflicts` (serial vs parallel), on a single
+// core: the parallel path costs a fixed ~25 µs extra (22 vs 47 µs at 50 courses, 65 vs 91 at
+// 150, 130 vs 156 at 300) and is even from 450 on. A second core saves at most half the serial
+// pass: ~32 µs at 150 courses, barely above the overhead, ~65 µs at 300. One core only shows
+// the overhead side, so re-run the bench on the target machine before moving the threshold.
+const PARALLEL_CONFLICT_MIN_COURSES: usize = 300;
+
+// Total SKS and the courses contributing to it
+type DailyLoad = (u32, Vec<u32>);
+
+// Courses of each day sorted by start time, shared by the overlap checks so each
+// only sweeps the sessions that can actually intersect
+struct IntervalIndex<'a> {
+    days: HashMap<u32, Vec<&'a OptimizedCourse>>,
+}
+
+impl<'a> IntervalIndex<'a> {
+    fn new(courses: impl IntoIterator<Item = &'a OptimizedCourse>) -> Self {
+        let mut days: HashMap<u32, Vec<&'a OptimizedCourse>> = HashMap::new();
+        for course in courses {
+            days.entry(course.hari).or_default().push(course);
+        }
+        for sessions in days.values_mut() {
+            sessions.sort_by_key(|c| c.jam_mulai);
+        }
+
+        Self { days }
+    }
+
+    // Sweep line: a session only meets those started earlier that have not ended yet
+    fn overlapping_pairs(&self) -> Vec<(&'a OptimizedCourse, &'a OptimizedCourse)> {
+        let mut pairs = Vec::new();
+        for sessions in self.days.values() {
+            let mut active: Vec<&OptimizedCourse> = Vec::new();
+            for &course in sessions {
+                active.retain(|a| a.jam_akhir > course.jam_mulai);
+                pairs.extend(
+                    active
+                        .iter()
+                        .filter(|a| ScheduleChecker::is_overlap(a, course))
+                        .map(|&a| (a, course)),
+                );
+                active.push(course);
+            }
+        }
+
+        pairs
+    }
+}
+
+impl ConstraintConfig {
+    /// Daily SKS limit for a class of `pertemuan` meetings a week, the most specific override wins.
+    /// Without one a class of exactly 4 meetings gets one course a day, as the decoder always did.
+    pub fn max_sks_kelas(&self, semester: u32, id_kelas: u32, pertemuan: usize) -> u32 {
+        self.batas_sks_kelas
+            .iter()
+            .filter(|l| l.semester == semester && l.id_kelas.is_none_or(|k| k == id_kelas))
+            .max_by_key(|l| l.id_kelas.is_some())
+            .map(|l| l.max_sks)
+            .or(self.max_sks_kelas_per_hari)
+            .unwrap_or(if pertemuan == 4 { DEFAULT_MAX_SKS_KELAS_4_PERTEMUAN } else { DEFAULT_MAX_SKS_KELAS_PER_HARI })
+    }
+
+    /// Days courses may be placed on, in scheduling order
+    pub fn active_days(&self) -> Vec<u32> {
+        match &self.hari_aktif {
+            Some(days) if !days.is_empty() => days.clone(),
+            _ => DEFAULT_HARI_AKTIF.to_vec(),
+        }
+    }
+
+    /// Start/end minutes of a time window, unknown ids fall back to the first window
+    pub fn time_window(&self, id_waktu: u32) -> TimeRange {
+        let windows = self.waktu.as_deref().filter(|w| !w.is_empty()).unwrap_or(&DEFAULT_WAKTU);
+        let window = windows.iter().find(|w| w.id_waktu == id_waktu).unwrap_or(&windows[0]);
+
+        TimeRange { mulai: window.mulai, akhir: window.akhir }
+    }
+
+    /// Minutes per week courses can be scheduled in, across all windows and active days
+    pub fn weekly_minutes(&self) -> u32 {
+        let windows = self.waktu.as_deref().filter(|w| !w.is_empty()).unwrap_or(&DEFAULT_WAKTU);
+        let per_day: u32 = windows.iter().map(|w| w.akhir.saturating_sub(w.mulai)).sum();
+
+        per_day * self.active_days().len() as u32
+    }
+
+    /// Building of a room, if the room is known
+    pub fn gedung(&self, id_ruangan: u32) -> Option<u32> {
+        self.ruangan.iter().find(|r| r.id_ruangan == id_ruangan).map(|r| r.gedung)
+    }
+
+    /// Intervals on the given day during which no course may run
+    pub fn blocked_ranges(&self, hari: u32) -> Vec<TimeRange> {
+        let day_blocks = self
+            .blok_hari
+            .iter()
+            .filter(|b| b.hari == hari)
+            .map(|b| TimeRange { mulai: b.mulai, akhir: b.akhir });
+
+        self.jam_istirahat.into_iter().chain(day_blocks).collect()
+    }
+}
+
 impl ScheduleChecker {
-    pub fn new(time_preferences: Vec<TimePreferenceRequest>) -> Self {
+    pub fn new(time_preferences: Vec<LecturerPreference>, config: ConstraintConfig, weights: PenaltyWeights) -> Self {
         Self {
             time_preferences: time_preferences
             .into_iter()
             .map(|p| (p.id_dosen, p))
             .collect(),
+            config,
+            weights,
+            custom_constraints: vec![],
+            parallel_conflicts_from: PARALLEL_CONFLICT_MIN_COURSES,
         }
     }
 
-    pub fn evaluate(&self, schedule: &[OptimizedCourse]) -> f64 {
-        let fitness_a = self.detect_conflicts(schedule);
-        let fitness_b = self.check_preferences(schedule);
+    /// Add a custom rule, evaluated after the built-in constraints
+    pub fn register(&mut self, constraint: impl Constraint + 'static) {
+        self.custom_constraints.push(Arc::new(constraint));
+    }
+
+    // Conflicts are hard unless weighted 0, lecturer preferences are soft, the rest declare their kind below
+    pub fn evaluate(&self, schedule: &[OptimizedCourse]) -> Evaluation {
+        self.evaluate_group(schedule) + self.evaluate_across_groups(schedule)
+    }
+
+    /// The part of `evaluate` that stays within a class group (prodi, semester, id_kelas, id_waktu)
+    /// and does not depend on rooms. Summed over the groups of a schedule it equals the same part
+    /// of the whole schedule, so the optimizer only re-checks the groups a particle changed.
+    pub fn evaluate_group(&self, group: &[OptimizedCourse]) -> Evaluation {
+        let mut evaluation = Evaluation {
+            soft_penalty: group.iter().filter_map(|course| self.course_preference(course)).map(|v| v.penalty).sum::<u32>() as f64,
+            ..Default::default()
+        };
+        Self::add_constraint_results(&mut evaluation, self.check_group_constraints(group));
+        evaluation
+    }
+
+    /// The rest of `evaluate`: conflicts, back-to-back preferences and constraints across class groups
+    pub fn evaluate_across_groups(&self, schedule: &[OptimizedCourse]) -> Evaluation {
+        let conflicts = self.overlap_conflicts(schedule);
 
-        (fitness_a.penalty + fitness_b.penalty) as f64
+        let mut evaluation = Evaluation {
+            hard_violations: conflicts.iter().filter(|c| c.severity == Severity::Hard).count() as u32,
+            hard_penalty: conflicts.iter().map(|c| c.penalty).sum::<u32>() as f64,
+            soft_penalty: self.check_back_to_back(schedule).iter().map(|v| v.penalty).sum::<u32>() as f64,
+        };
+        Self::add_constraint_results(&mut evaluation, self.check_cross_group_constraints(schedule));
+        evaluation
     }
 
-    pub fn evaluate_messages(&self, schedule: &[OptimizedCourse]) -> (Vec<ConflictMessage>, Vec<PreferenceMessage>) {
+    fn add_constraint_results(evaluation: &mut Evaluation, results: Vec<(&'static str, ConstraintKind, ConstraintResult)>) {
+        for (_, kind, result) in results {
+            match kind {
+                ConstraintKind::Hard => {
+                    evaluation.hard_violations += result.violations.len() as u32;
+                    evaluation.hard_penalty += result.penalty as f64;
+                }
+                ConstraintKind::Soft => evaluation.soft_penalty += result.penalty as f64,
+            }
+        }
+    }
+
+    pub fn evaluate_messages(&self, schedule: &[OptimizedCourse]) -> EvaluationReport {
         let conflict_result = self.detect_conflicts(schedule);
         let preference_result = self.check_preferences(schedule);
 
-        (conflict_result.conflicts, preference_result.violations)
+        let mut report = EvaluationReport {
+            is_feasible: conflict_result.conflicts.iter().all(|c| c.severity != Severity::Hard),
+            conflicts: conflict_result.conflicts,
+            preference_violations: preference_result.violations,
+            ..Default::default()
+        };
+
+        for (_, kind, result) in self.check_constraints(schedule) {
+            match kind {
+                ConstraintKind::Hard => report.hard_violations.extend(result.violations),
+                ConstraintKind::Soft => report.soft_violations.extend(result.violations),
+            }
+        }
+        report.is_feasible &= report.hard_violations.is_empty();
+
+        report
     }
 
-    // Detects scheduling conflicts such as overlapping classes or conflicting lecturers
-    pub fn detect_conflicts(&self, schedule: &[OptimizedCourse]) -> ConflictResult {
-        let mut conflicts = Vec::new();
+    /// Penalty per constraint type, so it is visible which ones the optimizer still struggles with
+    pub fn breakdown(&self, schedule: &[OptimizedCourse]) -> BTreeMap<&'static str, f64> {
+        let mut breakdown: BTreeMap<&'static str, f64> =
+            ["dosen_conflicts", "class_conflicts", "room_conflicts", "cohort_conflicts"]
+                .into_iter()
+                .map(|key| (key, 0.0))
+                .collect();
 
-        for (i, a) in schedule.iter().enumerate() {
-            for b in &schedule[i + 1..] {
-                if a.hari == b.hari && Self::is_overlap(a, b) && a.id_dosen == b.id_dosen {
-                    conflicts.push(ConflictMessage {
-                        jadwal_a: a.id_jadwal,
-                        jadwal_b: b.id_jadwal,
-                        deskripsi: format!(
-                            "Konflik dosen yang sama: dosen {} di dua kelas berbeda.",
-                            a.id_dosen
-                        ),
-                    });
-                }
+        for conflict in self.detect_conflicts(schedule).conflicts {
+            let key = match conflict.jenis {
+                ConflictType::Dosen => "dosen_conflicts",
+                ConflictType::Kelas => "class_conflicts",
+                ConflictType::Ruangan => "room_conflicts",
+                ConflictType::Kohort => "cohort_conflicts",
+                // Counted with the preferences and dosen_breaks below
+                ConflictType::Berurutan | ConflictType::Jeda => continue,
+            };
+            *breakdown.entry(key).or_default() += conflict.penalty as f64;
+        }
+        breakdown.insert("preference_violations", self.check_preferences(schedule).penalty as f64);
+
+        for (name, _, result) in self.check_constraints(schedule) {
+            breakdown.insert(name, result.penalty as f64);
+        }
+
+        breakdown
+    }
+
+    // Runs the configurable constraints, each tagged with its name and whether it is hard or soft
+    pub fn check_constraints(&self, schedule: &[OptimizedCourse]) -> Vec<(&'static str, ConstraintKind, ConstraintResult)> {
+        let mut results = self.check_group_constraints(schedule);
+        results.extend(self.check_cross_group_constraints(schedule));
+        results
+    }
+
+    // Constraints that only compare courses of the same class group and ignore rooms
+    fn check_group_constraints(&self, schedule: &[OptimizedCourse]) -> Vec<(&'static str, ConstraintKind, ConstraintResult)> {
+        use ConstraintKind::{Hard, Soft};
+
+        vec![
+            ("kelas_daily_load", Hard, self.check_kelas_daily_load(schedule)),
+            ("blocked_ranges", Hard, self.check_blocked_ranges(schedule)),
+            ("active_days", Hard, self.check_active_days(schedule)),
+            ("forbidden_slots", Hard, self.check_forbidden_slots(schedule)),
+            ("split_meetings", Hard, self.check_split_meetings(schedule)),
+            ("day_balance", Soft, self.check_day_balance(schedule)),
+            ("kelas_idle_gaps", Soft, self.check_kelas_idle_gaps(schedule)),
+            ("stability", Soft, self.check_stability(schedule)),
+            ("absences", Soft, self.check_absences(schedule)),
+        ]
+    }
+
+    // Constraints across class groups (per lecturer, cohort, room, ...) and custom rules
+    fn check_cross_group_constraints(&self, schedule: &[OptimizedCourse]) -> Vec<(&'static str, ConstraintKind, ConstraintResult)> {
+        use ConstraintKind::{Hard, Soft};
+
+        vec![
+            ("dosen_daily_load", Hard, self.check_dosen_daily_load(schedule)),
+            ("dosen_weekly_load", Hard, self.check_dosen_weekly_load(schedule)),
+            ("evening_load", Hard, self.check_evening_load(schedule)),
+            ("same_slot_groups", Hard, self.check_same_slot_groups(schedule)),
+            ("precedences", Hard, self.check_precedences(schedule)),
+            ("section_spread", Soft, self.check_section_spread(schedule)),
+            ("room_balance", Soft, self.check_room_balance(schedule)),
+            ("dosen_idle_gaps", Soft, self.check_dosen_idle_gaps(schedule)),
+            ("teaching_days", Soft, self.check_teaching_days(schedule)),
+            ("building_travel", Soft, self.check_building_travel(schedule)),
+            ("dosen_breaks", Soft, self.check_dosen_breaks(schedule)),
+        ]
+        .into_iter()
+        .chain(self.custom_constraints.iter().map(|constraint| {
+            let (penalty, violations) = constraint.penalty(schedule);
+            (constraint.name(), constraint.kind(), ConstraintResult { penalty, violations })
+        }))
+        .collect()
+    }
+
+    // Penalizes lecturers whose total SKS on one day exceeds the configured limit
+    pub fn check_dosen_daily_load(&self, schedule: &[OptimizedCourse]) -> ConstraintResult {
+        let Some(max_sks) = self.config.max_sks_dosen_per_hari else {
+            return ConstraintResult::default();
+        };
+
+        let mut per_day: HashMap<(u32, u32), DailyLoad> = HashMap::new();
+        for course in schedule {
+            let entry = per_day.entry((course.id_dosen, course.hari)).or_default();
+            entry.0 += course.sks;
+            entry.1.push(course.id_jadwal);
+        }
+
+        let violations: Vec<ConstraintMessage> = per_day
+            .into_iter()
+            .filter(|(_, (total_sks, _))| *total_sks > max_sks)
+            .map(|((id_dosen, hari), (total_sks, id_jadwal))| ConstraintMessage {
+                id_jadwal,
+                deskripsi: format!(
+                    "Beban dosen {} pada hari {} adalah {} SKS, melebihi batas {} SKS.",
+                    id_dosen, nama_hari(hari), total_sks, max_sks
+                ),
+            })
+            .collect();
+
+        ConstraintResult {
+            penalty: violations.len() as u32 * self.weights.dosen_daily_load,
+            violations,
+        }
+    }
+
+    // Penalizes lecturers assigned more courses per week than their metadata allows
+    pub fn check_dosen_weekly_load(&self, schedule: &[OptimizedCourse]) -> ConstraintResult {
+        let mut per_dosen: HashMap<u32, Vec<u32>> = HashMap::new();
+        for course in schedule {
+            per_dosen.entry(course.id_dosen).or_default().push(course.id_jadwal);
+        }
+        // Both meetings of a split course are still one class
+        for id_jadwal in per_dosen.values_mut() {
+            id_jadwal.sort_unstable();
+            id_jadwal.dedup();
+        }
+
+        let mut result = ConstraintResult::default();
+        for (id_dosen, id_jadwal) in per_dosen {
+            let Some(max_kelas) = self.time_preferences.get(&id_dosen).and_then(|p| p.max_kelas_per_minggu) else {
+                continue;
+            };
+            let count = id_jadwal.len() as u32;
+            if count <= max_kelas {
+                continue;
             }
+
+            result.penalty += (count - max_kelas) * self.weights.dosen_weekly_load;
+            result.violations.push(ConstraintMessage {
+                id_jadwal,
+                deskripsi: format!(
+                    "Dosen {} mengajar {} kelas per minggu, melebihi batas {} kelas.",
+                    id_dosen, count, max_kelas
+                ),
+            });
+        }
+
+        result
+    }
+
+    // Penalizes lecturers teaching on more evenings per week than they accept
+    pub fn check_evening_load(&self, schedule: &[OptimizedCourse]) -> ConstraintResult {
+        let mut per_dosen: HashMap<u32, (Vec<u32>, Vec<u32>)> = HashMap::new();
+        for course in schedule.iter().filter(|c| c.id_waktu == ID_WAKTU_MALAM) {
+            let entry = per_dosen.entry(course.id_dosen).or_default();
+            if !entry.0.contains(&course.hari) {
+                entry.0.push(course.hari);
+            }
+            entry.1.push(course.id_jadwal);
+        }
+
+        let mut result = ConstraintResult::default();
+        for (id_dosen, (days, id_jadwal)) in per_dosen {
+            let Some(max_malam) = self.time_preferences.get(&id_dosen).and_then(|p| p.max_malam_per_minggu) else {
+                continue;
+            };
+            let count = days.len() as u32;
+            if count <= max_malam {
+                continue;
+            }
+
+            result.penalty += (count - max_malam) * self.weights.evening_load;
+            result.violations.push(ConstraintMessage {
+                id_jadwal,
+                deskripsi: format!(
+                    "Dosen {} mengajar malam pada {} hari per minggu, melebihi batas {} malam.",
+                    id_dosen, count, max_malam
+                ),
+            });
+        }
+
+        result
+    }
+
+    // Penalizes classes whose total SKS on one day exceeds their configured limit
+    pub fn check_kelas_daily_load(&self, schedule: &[OptimizedCourse]) -> ConstraintResult {
+        let mut per_day: HashMap<(u32, u32, u32, u32, u32), DailyLoad> = HashMap::new();
+        let mut per_class: HashMap<ClassKey, usize> = HashMap::new();
+        for course in schedule {
+            let key = (course.prodi, course.semester, course.id_kelas, course.id_waktu, course.hari);
+            let entry = per_day.entry(key).or_default();
+            entry.0 += course.sks;
+            entry.1.push(course.id_jadwal);
+            *per_class.entry((course.prodi, course.semester, course.id_kelas, course.id_waktu)).or_default() += 1;
         }
 
+        let violations: Vec<ConstraintMessage> = per_day
+            .into_iter()
+            .filter_map(|((prodi, semester, id_kelas, id_waktu, hari), (total_sks, id_jadwal))| {
+                let pertemuan = per_class[&(prodi, semester, id_kelas, id_waktu)];
+                let max_sks = self.config.max_sks_kelas(semester, id_kelas, pertemuan);
+                (total_sks > max_sks).then(|| ConstraintMessage {
+                    id_jadwal,
+                    deskripsi: format!(
+                        "Kelas {} semester {} prodi {} pada hari {} memiliki {} SKS, melebihi batas {} SKS.",
+                        id_kelas, semester, prodi, nama_hari(hari), total_sks, max_sks
+                    ),
+                })
+            })
+            .collect();
+
+        ConstraintResult {
+            penalty: violations.len() as u32 * self.weights.class_daily_load,
+            violations,
+        }
+    }
+
+    // Every conflicting pair for the report: the hard overlaps, then the soft pairs whose penalty
+    // is already part of the preferences and constraints
+    pub fn detect_conflicts(&self, schedule: &[OptimizedCourse]) -> ConflictResult {
+        let mut conflicts = self.overlap_conflicts(schedule);
+        conflicts.extend(self.soft_conflicts(schedule));
+
         ConflictResult {
-            penalty: conflicts.len() as u32 * 100,
+            penalty: conflicts.iter().map(|c| c.penalty).sum(),
             conflicts,
         }
     }
 
+    // Detects scheduling conflicts such as overlapping classes or conflicting lecturers.
+    // Only courses sharing a day and a lecturer/class/room can clash, so pairs are compared per bucket.
+    fn overlap_conflicts(&self, schedule: &[OptimizedCourse]) -> Vec<ConflictMessage> {
+        let mut conflicts = Vec::new();
 
-    pub fn check_preferences(&self, schedule: &[OptimizedCourse]) -> PreferenceResult {
-        let violations: Vec<PreferenceMessage> = schedule.par_iter()
+        for (a, b) in self.overlapping_pairs(schedule, |c| Some(c.id_dosen)) {
+            conflicts.push(self.conflict(
+                a,
+                b,
+                ConflictType::Dosen,
+                format!(
+                    "Konflik dosen yang sama: dosen {} di dua kelas berbeda.",
+                    a.id_dosen
+                ),
+            ));
+        }
+
+        for (a, b) in self.overlapping_pairs(schedule, |c| Some((c.prodi, c.semester, c.id_kelas))) {
+            conflicts.push(self.conflict(
+                a,
+                b,
+                ConflictType::Kelas,
+                format!(
+                    "Konflik kelas: kelas {} semester {} prodi {} memiliki dua jadwal sekaligus.",
+                    a.id_kelas, a.semester, a.prodi
+                ),
+            ));
+        }
+
+        for (a, b) in self.overlapping_pairs(schedule, |c| (c.ruangan != 0).then_some(c.ruangan)) {
+            conflicts.push(self.conflict(
+                a,
+                b,
+                ConflictType::Ruangan,
+                format!(
+                    "Konflik ruangan: ruangan {} dipakai dua jadwal sekaligus.",
+                    a.ruangan
+                ),
+            ));
+        }
+
+        conflicts.extend(self.detect_cohort_conflicts(schedule));
+        conflicts
+    }
+
+    // Pairs of a lecturer's sessions that only cost a soft penalty
+    fn soft_conflicts(&self, schedule: &[OptimizedCourse]) -> Vec<ConflictMessage> {
+        let back_to_back = self.back_to_back_pairs(schedule).into_iter().map(|(a, b, deskripsi)| {
+            let prioritas = self.time_preferences[&a.id_dosen].prioritas;
+            self.soft_conflict(a, b, ConflictType::Berurutan, self.weights.back_to_back * prioritas, deskripsi)
+        });
+        let breaks = self
+            .short_breaks(schedule)
+            .into_iter()
+            .map(|(a, b, deskripsi)| self.soft_conflict(a, b, ConflictType::Jeda, self.weights.dosen_break, deskripsi));
+
+        back_to_back.chain(breaks).collect()
+    }
+
+    /// Courses the given course clashes with at its current day/time, itself excluded
+    pub fn conflicting_courses(&self, schedule: &[OptimizedCourse], course: &OptimizedCourse) -> Vec<u32> {
+        let cohorts: Vec<&Vec<u32>> = self
+            .config
+            .kohort
+            .iter()
+            .map(|k| &k.id_jadwal)
+            .filter(|ids| ids.contains(&course.id_jadwal))
+            .collect();
+
+        schedule
+            .iter()
+            .filter(|other| {
+                (other.id_jadwal, other.pertemuan) != (course.id_jadwal, course.pertemuan)
+                    && other.hari == course.hari
+                    && Self::is_overlap(other, course)
+            })
+            .filter(|other| {
+                other.id_dosen == course.id_dosen
+                    || (other.prodi, other.semester, other.id_kelas) == (course.prodi, course.semester, course.id_kelas)
+                    || (other.ruangan != 0 && other.ruangan == course.ruangan)
+                    || cohorts.iter().any(|ids| ids.contains(&other.id_jadwal))
+            })
+            .map(|other| other.id_jadwal)
+            .collect()
+    }
+
+    // Flat weight per conflict, or scaled by overlapping minutes and SKS involved when configured.
+    // A full overlap of two 3-SKS courses (120 minutes, 6 SKS) costs exactly the flat weight.
+    // Conflict types weighted 0 are still reported, but only as warnings.
+    fn conflict(&self, a: &OptimizedCourse, b: &OptimizedCourse, jenis: ConflictType, deskripsi: String) -> ConflictMessage {
+        let weight = self.conflict_weight(jenis);
+        let penalty = if weight == 0 {
+            0
+        } else if self.weights.scale_conflicts_by_overlap {
+            let overlap = a.jam_akhir.min(b.jam_akhir).saturating_sub(a.jam_mulai.max(b.jam_mulai));
+            (weight * overlap * (a.sks + b.sks)).div_ceil(720).max(1)
+        } else {
+            weight
+        };
+
+        ConflictMessage {
+            jadwal_a: a.id_jadwal,
+            jadwal_b: b.id_jadwal,
+            jenis,
+            severity: if weight == 0 { Severity::Warning } else { Severity::Hard },
+            penalty,
+            deskripsi,
+        }
+    }
+
+    fn soft_conflict(&self, a: &OptimizedCourse, b: &OptimizedCourse, jenis: ConflictType, penalty: u32, deskripsi: String) -> ConflictMessage {
+        ConflictMessage {
+            jadwal_a: a.id_jadwal,
+            jadwal_b: b.id_jadwal,
+            jenis,
+            severity: if penalty == 0 { Severity::Warning } else { Severity::Soft },
+            penalty,
+            deskripsi,
+        }
+    }
+
+    fn conflict_weight(&self, jenis: ConflictType) -> u32 {
+        match jenis {
+            ConflictType::Dosen => self.weights.dosen_conflict,
+            ConflictType::Kelas => self.weights.class_conflict,
+            ConflictType::Ruangan => self.weights.room_conflict,
+            ConflictType::Kohort => self.weights.cohort_conflict,
+            ConflictType::Berurutan => self.weights.back_to_back,
+            ConflictType::Jeda => self.weights.dosen_break,
+        }
+    }
+
+    // Overlaps between courses shared by one cohort, e.g. electives taken across classes
+    fn detect_cohort_conflicts(&self, schedule: &[OptimizedCourse]) -> Vec<ConflictMessage> {
+        if self.config.kohort.is_empty() {
+            return vec![];
+        }
+
+        let by_id = meetings_by_id(schedule);
+        let mut conflicts = Vec::new();
+
+        for cohort in &self.config.kohort {
+            let members = IntervalIndex::new(cohort.id_jadwal.iter().filter_map(|id| by_id.get(id)).flatten().copied());
+
+            for (a, b) in members.overlapping_pairs() {
+                conflicts.push(self.conflict(
+                    a,
+                    b,
+                    ConflictType::Kohort,
+                    format!(
+                        "Konflik kohort {}: jadwal {} dan {} diikuti mahasiswa yang sama.",
+                        cohort.id_kohort, a.id_jadwal, b.id_jadwal
+                    ),
+                ));
+            }
+        }
+
+        conflicts
+    }
+
+    // Penalizes courses that run into a blocked interval such as the lunch break
+    pub fn check_blocked_ranges(&self, schedule: &[OptimizedCourse]) -> ConstraintResult {
+        let violations: Vec<ConstraintMessage> = schedule
+            .iter()
             .filter_map(|course| {
-                let pref = self.time_preferences.get(&course.id_dosen)?;
-
-                let hari_idx = (course.hari - 1) as usize;
-                let waktu_ok = if course.jam_mulai < 1080 {
-                    match hari_idx {
-                        0 => pref.senin_pagi,
-                        1 => pref.selasa_pagi,
-                        2 => pref.rabu_pagi,
-                        3 => pref.kamis_pagi,
-                        4 => pref.jumat_pagi,
-                        _ => false,
-                    }
-                } else {
-                    match hari_idx {
-                        0 => pref.senin_malam,
-                        1 => pref.selasa_malam,
-                        2 => pref.rabu_malam,
-                        3 => pref.kamis_malam,
-                        4 => pref.jumat_malam,
-                        _ => false,
-                    }
+                let block = self
+                    .config
+                    .blocked_ranges(course.hari)
+                    .into_iter()
+                    .find(|b| course.jam_mulai < b.akhir && b.mulai < course.jam_akhir)?;
+
+                Some(ConstraintMessage {
+                    id_jadwal: vec![course.id_jadwal],
+                    deskripsi: format!(
+                        "Jadwal {} ({}-{}) bertabrakan dengan waktu terlarang {}-{} pada hari {}.",
+                        course.id_jadwal,
+                        format_jam(course.jam_mulai),
+                        format_jam(course.jam_akhir),
+                        format_jam(block.mulai),
+                        format_jam(block.akhir),
+                        nama_hari(course.hari)
+                    ),
+                })
+            })
+            .collect();
+
+        ConstraintResult {
+            penalty: violations.len() as u32 * self.weights.blocked_time,
+            violations,
+        }
+    }
+
+    // Penalizes courses placed on a day that is not open for scheduling
+    pub fn check_active_days(&self, schedule: &[OptimizedCourse]) -> ConstraintResult {
+        let days = self.config.active_days();
+        let violations: Vec<ConstraintMessage> = schedule
+            .iter()
+            .filter(|course| !days.contains(&course.hari))
+            .map(|course| ConstraintMessage {
+                id_jadwal: vec![course.id_jadwal],
+                deskripsi: format!(
+                    "Jadwal {} ditempatkan pada hari {} yang tidak aktif.",
+                    course.id_jadwal,
+                    nama_hari(course.hari)
+                ),
+            })
+            .collect();
+
+        ConstraintResult {
+            penalty: violations.len() as u32 * self.weights.inactive_day,
+            violations,
+        }
+    }
+
+    // Penalizes courses placed in a day/window blacklisted for them
+    pub fn check_forbidden_slots(&self, schedule: &[OptimizedCourse]) -> ConstraintResult {
+        if self.config.slot_terlarang.is_empty() {
+            return ConstraintResult::default();
+        }
+
+        let violations: Vec<ConstraintMessage> = schedule
+            .iter()
+            .filter(|course| {
+                self.config.slot_terlarang.iter().any(|slot| {
+                    slot.id_jadwal == course.id_jadwal
+                        && slot.hari == course.hari
+                        && slot.id_waktu.is_none_or(|w| w == course.id_waktu)
+                })
+            })
+            .map(|course| ConstraintMessage {
+                id_jadwal: vec![course.id_jadwal],
+                deskripsi: format!(
+                    "Jadwal {} tidak boleh ditempatkan pada hari {} waktu {}.",
+                    course.id_jadwal,
+                    nama_hari(course.hari),
+                    course.id_waktu
+                ),
+            })
+            .collect();
+
+        ConstraintResult {
+            penalty: violations.len() as u32 * self.weights.forbidden_slot,
+            violations,
+        }
+    }
+
+    // Meetings of a split course must fall on different days
+    pub fn check_split_meetings(&self, schedule: &[OptimizedCourse]) -> ConstraintResult {
+        let mut per_jadwal: HashMap<u32, Vec<u32>> = HashMap::new();
+        for course in schedule.iter().filter(|c| c.pertemuan != 0) {
+            per_jadwal.entry(course.id_jadwal).or_default().push(course.hari);
+        }
+
+        let violations: Vec<ConstraintMessage> = per_jadwal
+            .into_iter()
+            .filter(|(_, days)| days.iter().enumerate().any(|(i, d)| days[i + 1..].contains(d)))
+            .map(|(id_jadwal, days)| ConstraintMessage {
+                id_jadwal: vec![id_jadwal],
+                deskripsi: format!(
+                    "Pertemuan jadwal {} yang dipecah berada pada hari yang sama ({}).",
+                    id_jadwal,
+                    days.iter().map(|&d| nama_hari(d)).collect::<Vec<_>>().join(", ")
+                ),
+            })
+            .collect();
+
+        ConstraintResult {
+            penalty: violations.len() as u32 * self.weights.split_same_day,
+            violations,
+        }
+    }
+
+    // Penalizes members of a same-slot group that are not in the group's most common slot
+    pub fn check_same_slot_groups(&self, schedule: &[OptimizedCourse]) -> ConstraintResult {
+        if self.config.grup_slot_sama.is_empty() {
+            return ConstraintResult::default();
+        }
+
+        let by_id = meetings_by_id(schedule);
+        let mut result = ConstraintResult::default();
+
+        for group in &self.config.grup_slot_sama {
+            // Split courses are matched meeting by meeting
+            let mut per_meeting: BTreeMap<u32, Vec<&OptimizedCourse>> = BTreeMap::new();
+            for course in group.iter().filter_map(|id| by_id.get(id)).flatten() {
+                per_meeting.entry(course.pertemuan).or_default().push(course);
+            }
+
+            for members in per_meeting.values() {
+                let slot = |c: &OptimizedCourse| (c.hari, c.jam_mulai);
+                let Some(anchor) = members
+                    .iter()
+                    .max_by_key(|a| members.iter().filter(|b| slot(a) == slot(b)).count())
+                else {
+                    continue;
                 };
 
-                if waktu_ok {
-                    None
-                } else {
-                    let waktu_str = if course.jam_mulai < 1080 { "pagi" } else { "malam" };
-                    let hari_str = match course.hari {
-                        1 => "Senin",
-                        2 => "Selasa",
-                        3 => "Rabu",
-                        4 => "Kamis",
-                        5 => "Jumat",
-                        _ => "Hari Tidak Dikenal",
-                    };
-
-                    Some(PreferenceMessage {
-                        id_jadwal: course.id_jadwal,
-                        id_dosen: course.id_dosen,
-                        hari: course.hari,
-                        jam_mulai: course.jam_mulai,
+                for course in members.iter().filter(|c| slot(c) != slot(anchor)) {
+                    result.penalty += self.weights.same_slot;
+                    result.violations.push(ConstraintMessage {
+                        id_jadwal: vec![anchor.id_jadwal, course.id_jadwal],
                         deskripsi: format!(
-                            "Dosen {} tidak prefer jadwal {} {}.",
-                            course.id_dosen, hari_str, waktu_str
+                            "Jadwal {} harus bersamaan dengan jadwal {} ({} {}).",
+                            course.id_jadwal,
+                            anchor.id_jadwal,
+                            nama_hari(anchor.hari),
+                            format_jam(anchor.jam_mulai)
                         ),
-                    })
+                    });
+                }
+            }
+        }
+
+        result
+    }
+
+    // Penalizes ordered pairs where the first course is not on an earlier day than the second
+    pub fn check_precedences(&self, schedule: &[OptimizedCourse]) -> ConstraintResult {
+        if self.config.urutan_hari.is_empty() {
+            return ConstraintResult::default();
+        }
+
+        let by_id = meetings_by_id(schedule);
+        let violations: Vec<ConstraintMessage> = self
+            .config
+            .urutan_hari
+            .iter()
+            .filter_map(|p| Some((by_id.get(&p.sebelum)?, by_id.get(&p.sesudah)?)))
+            // Every meeting of the first course has to come before every meeting of the second
+            .flat_map(|(before, after)| before.iter().flat_map(move |a| after.iter().map(move |b| (a, b))))
+            .filter(|(a, b)| a.hari >= b.hari)
+            .map(|(a, b)| ConstraintMessage {
+                id_jadwal: vec![a.id_jadwal, b.id_jadwal],
+                deskripsi: format!(
+                    "Jadwal {} ({}) harus lebih awal dari jadwal {} ({}).",
+                    a.id_jadwal,
+                    nama_hari(a.hari),
+                    b.id_jadwal,
+                    nama_hari(b.hari)
+                ),
+            })
+            .collect();
+
+        ConstraintResult {
+            penalty: violations.len() as u32 * self.weights.precedence,
+            violations,
+        }
+    }
+
+    // Soft objective: sections of one matkul for the same cohort should land on different days
+    pub fn check_section_spread(&self, schedule: &[OptimizedCourse]) -> ConstraintResult {
+        let Some(weight) = self.config.bobot_sebaran else {
+            return ConstraintResult::default();
+        };
+
+        let mut per_day: HashMap<(u32, u32, u32, u32), Vec<u32>> = HashMap::new();
+        for course in schedule {
+            per_day
+                .entry((course.prodi, course.semester, course.id_matkul, course.hari))
+                .or_default()
+                .push(course.id_jadwal);
+        }
+
+        let mut result = ConstraintResult::default();
+        for ((prodi, semester, id_matkul, hari), id_jadwal) in per_day {
+            let pairs = (id_jadwal.len() * id_jadwal.len().saturating_sub(1) / 2) as u32;
+            if pairs == 0 {
+                continue;
+            }
+
+            result.penalty += pairs * weight;
+            result.violations.push(ConstraintMessage {
+                deskripsi: format!(
+                    "{} seksi matkul {} (prodi {} semester {}) menumpuk pada hari {}.",
+                    id_jadwal.len(), id_matkul, prodi, semester, nama_hari(hari)
+                ),
+                id_jadwal,
+            });
+        }
+
+        result
+    }
+
+    // Soft objective: a class's SKS should be spread evenly over the active days
+    pub fn check_day_balance(&self, schedule: &[OptimizedCourse]) -> ConstraintResult {
+        let Some(weight) = self.config.bobot_keseimbangan else {
+            return ConstraintResult::default();
+        };
+
+        let days = self.config.active_days();
+        let mut per_class: HashMap<ClassKey, (Vec<u32>, Vec<u32>)> = HashMap::new();
+        for course in schedule {
+            let entry = per_class
+                .entry((course.prodi, course.semester, course.id_kelas, course.id_waktu))
+                .or_insert_with(|| (vec![0; days.len()], vec![]));
+            if let Some(i) = days.iter().position(|&d| d == course.hari) {
+                entry.0[i] += course.sks;
+            }
+            entry.1.push(course.id_jadwal);
+        }
+
+        let n = days.len() as u32;
+        let mut result = ConstraintResult::default();
+        for ((prodi, semester, id_kelas, _), (sks_per_day, id_jadwal)) in per_class {
+            let total: u32 = sks_per_day.iter().sum();
+            let deviation: u32 = sks_per_day.iter().map(|&sks| (sks * n).abs_diff(total)).sum::<u32>() / n;
+            if deviation == 0 {
+                continue;
+            }
+
+            result.penalty += deviation * weight;
+            result.violations.push(ConstraintMessage {
+                id_jadwal,
+                deskripsi: format!(
+                    "Sebaran SKS kelas {} semester {} prodi {} per hari tidak merata: {:?}.",
+                    id_kelas, semester, prodi, sks_per_day
+                ),
+            });
+        }
+
+        result
+    }
+
+    /// Share of sessions placed in a preferred slot, overall and per lecturer
+    pub fn preference_satisfaction(&self, schedule: &[OptimizedCourse]) -> PreferenceSatisfaction {
+        let mut per_dosen: HashMap<u32, (u32, u32)> = HashMap::new();
+        for course in schedule {
+            // Lecturers without preferences accept every slot
+            let preferred = self
+                .time_preferences
+                .get(&course.id_dosen)
+                .is_none_or(|p| p.level(course.hari, course.jam_mulai, course.jam_akhir) == PreferenceLevel::Prefer);
+            let entry = per_dosen.entry(course.id_dosen).or_default();
+            entry.0 += 1;
+            entry.1 += preferred as u32;
+        }
+
+        let persen = |prefer: u32, total: u32| if total == 0 { 0.0 } else { prefer as f64 / total as f64 * 100.0 };
+        let (total, prefer) = per_dosen.values().fold((0, 0), |(t, p), &(n, ok)| (t + n, p + ok));
+
+        let mut per_dosen: Vec<LecturerSatisfaction> = per_dosen
+            .into_iter()
+            .map(|(id_dosen, (jumlah_jadwal, jumlah_prefer))| LecturerSatisfaction {
+                id_dosen,
+                jumlah_jadwal,
+                jumlah_prefer,
+                persen: persen(jumlah_prefer, jumlah_jadwal),
+            })
+            .collect();
+        per_dosen.sort_by_key(|l| l.id_dosen);
+
+        PreferenceSatisfaction { persen: persen(prefer, total), per_dosen }
+    }
+
+    /// Differences between two candidate schedules: moved courses, penalty per constraint and
+    /// preferred-slot share per lecturer
+    pub fn compare(&self, a: &[OptimizedCourse], b: &[OptimizedCourse]) -> ScheduleComparison {
+        let placements = |schedule: &[OptimizedCourse]| -> HashMap<(u32, u32), (u32, u32, u32)> {
+            schedule.iter().map(|c| ((c.id_jadwal, c.pertemuan), (c.hari, c.jam_mulai, c.ruangan))).collect()
+        };
+        let (placed_a, placed_b) = (placements(a), placements(b));
+
+        let mut dipindah: Vec<MovedCourse> = placed_a
+            .iter()
+            .filter_map(|(&(id_jadwal, pertemuan), &(dari_hari, dari_jam, dari_ruangan))| {
+                let &(ke_hari, ke_jam, ke_ruangan) = placed_b.get(&(id_jadwal, pertemuan))?;
+                ((dari_hari, dari_jam, dari_ruangan) != (ke_hari, ke_jam, ke_ruangan)).then_some(MovedCourse {
+                    id_jadwal,
+                    pertemuan,
+                    dari_hari,
+                    dari_jam,
+                    dari_ruangan,
+                    ke_hari,
+                    ke_jam,
+                    ke_ruangan,
+                })
+            })
+            .collect();
+        dipindah.sort_by_key(|m| (m.id_jadwal, m.pertemuan));
+
+        let only_in = |x: &HashMap<(u32, u32), _>, y: &HashMap<(u32, u32), _>| {
+            let mut ids: Vec<u32> = x.keys().filter(|k| !y.contains_key(*k)).map(|k| k.0).collect();
+            ids.sort_unstable();
+            ids.dedup();
+            ids
+        };
+
+        let breakdown_b = self.breakdown(b);
+        let penalti = self
+            .breakdown(a)
+            .into_iter()
+            .map(|(nama, penalty_a)| {
+                let penalty_b = breakdown_b.get(nama).copied().unwrap_or(0.0);
+                PenaltyChange { nama, a: penalty_a, b: penalty_b, selisih: penalty_b - penalty_a }
+            })
+            .filter(|change| change.selisih != 0.0)
+            .collect();
+
+        let satisfaction_b: HashMap<u32, f64> = self
+            .preference_satisfaction(b)
+            .per_dosen
+            .into_iter()
+            .map(|l| (l.id_dosen, l.persen))
+            .collect();
+        let kepuasan = self
+            .preference_satisfaction(a)
+            .per_dosen
+            .into_iter()
+            .filter_map(|l| {
+                let persen_b = *satisfaction_b.get(&l.id_dosen)?;
+                (persen_b != l.persen).then_some(SatisfactionChange {
+                    id_dosen: l.id_dosen,
+                    a: l.persen,
+                    b: persen_b,
+                    selisih: persen_b - l.persen,
+                })
+            })
+            .collect();
+
+        ScheduleComparison {
+            fitness_a: self.evaluate(a).fitness(),
+            fitness_b: self.evaluate(b).fitness(),
+            dipindah,
+            hanya_di_a: only_in(&placed_a, &placed_b),
+            hanya_di_b: only_in(&placed_b, &placed_a),
+            penalti,
+            kepuasan,
+        }
+    }
+
+    /// Applies a manual swap or move to a copy of the schedule and reports what it changes
+    pub fn try_edit(&self, schedule: &[OptimizedCourse], edit: &ScheduleEdit) -> Result<EditImpact, String> {
+        let find = |id_jadwal: u32, pertemuan: Option<u32>| {
+            schedule
+                .iter()
+                .position(|c| c.id_jadwal == id_jadwal && pertemuan.is_none_or(|p| c.pertemuan == p))
+                .ok_or_else(|| format!("Jadwal {} tidak ditemukan", id_jadwal))
+        };
+        // Moves a course to a new start while keeping its own duration
+        let place = |course: &mut OptimizedCourse, hari: u32, jam_mulai: u32| {
+            let durasi = course.jam_akhir.saturating_sub(course.jam_mulai);
+            course.hari = hari;
+            course.jam_mulai = jam_mulai;
+            course.jam_akhir = jam_mulai + durasi;
+        };
+
+        let mut edited = schedule.to_vec();
+        match *edit {
+            ScheduleEdit::Swap { id_a, id_b, pertemuan_a, pertemuan_b } => {
+                let (a, b) = (find(id_a, pertemuan_a)?, find(id_b, pertemuan_b)?);
+                let (slot_a, slot_b) = ((edited[a].hari, edited[a].jam_mulai), (edited[b].hari, edited[b].jam_mulai));
+                place(&mut edited[a], slot_b.0, slot_b.1);
+                place(&mut edited[b], slot_a.0, slot_a.1);
+                // Online courses hold no room, so there is nothing to exchange
+                if !edited[a].is_online && !edited[b].is_online {
+                    (edited[a].ruangan, edited[b].ruangan) = (edited[b].ruangan, edited[a].ruangan);
+                }
+            }
+            ScheduleEdit::Move { id_jadwal, hari, jam_mulai, pertemuan } => {
+                if !(1..=7).contains(&hari) {
+                    return Err(format!("Hari {} tidak valid", hari));
                 }
+                let index = find(id_jadwal, pertemuan)?;
+                place(&mut edited[index], hari, jam_mulai);
+                if edited[index].jam_akhir > 24 * 60 {
+                    return Err(format!("Jadwal {} melewati tengah malam bila dimulai {}", id_jadwal, format_jam(jam_mulai)));
+                }
+            }
+        }
+
+        // A conflict is the same one before and after when it involves the same pair in the same way
+        let key = |c: &ConflictMessage| (c.jadwal_a.min(c.jadwal_b), c.jadwal_a.max(c.jadwal_b), c.jenis);
+        let before = self.detect_conflicts(schedule).conflicts;
+        let after = self.detect_conflicts(&edited).conflicts;
+        let (keys_before, keys_after): (HashSet<_>, HashSet<_>) =
+            (before.iter().map(key).collect(), after.iter().map(key).collect());
+
+        let (fitness_sebelum, evaluation) = (self.evaluate(schedule).fitness(), self.evaluate(&edited));
+        Ok(EditImpact {
+            fitness_sebelum,
+            fitness_sesudah: evaluation.fitness(),
+            selisih: evaluation.fitness() - fitness_sebelum,
+            is_feasible: evaluation.is_feasible(),
+            konflik_baru: after.into_iter().filter(|c| !keys_before.contains(&key(c))).collect(),
+            konflik_hilang: before.into_iter().filter(|c| !keys_after.contains(&key(c))).collect(),
+            schedule: edited,
+        })
+    }
+
+    /// Weekly usage of every configured room
+    pub fn room_utilization(&self, schedule: &[OptimizedCourse]) -> Vec<RoomUsage> {
+        let capacity = self.config.weekly_minutes().max(1) as f64;
+
+        self.config
+            .ruangan
+            .iter()
+            .map(|room| {
+                let courses = schedule.iter().filter(|c| c.ruangan == room.id_ruangan);
+                let (jumlah_jadwal, menit_terpakai) =
+                    courses.fold((0, 0), |(n, menit), c| (n + 1, menit + c.jam_akhir.saturating_sub(c.jam_mulai)));
+
+                RoomUsage {
+                    id_ruangan: room.id_ruangan,
+                    gedung: room.gedung,
+                    jumlah_jadwal,
+                    menit_terpakai,
+                    persentase: menit_terpakai as f64 / capacity * 100.0,
+                }
+            })
+            .collect()
+    }
+
+    // Soft objective: penalizes rooms booked far above or below the average room
+    pub fn check_room_balance(&self, schedule: &[OptimizedCourse]) -> ConstraintResult {
+        let Some(weight) = self.config.bobot_utilisasi_ruangan else {
+            return ConstraintResult::default();
+        };
+
+        let usage = self.room_utilization(schedule);
+        if usage.len() < 2 {
+            return ConstraintResult::default();
+        }
+
+        let n = usage.len() as u32;
+        let total: u32 = usage.iter().map(|u| u.menit_terpakai).sum();
+        let mut result = ConstraintResult::default();
+        for room in usage {
+            // Whole hours away from the average
+            let deviation = (room.menit_terpakai * n).abs_diff(total) / n / 60;
+            if deviation == 0 {
+                continue;
+            }
+
+            result.penalty += deviation * weight;
+            result.violations.push(ConstraintMessage {
+                id_jadwal: schedule.iter().filter(|c| c.ruangan == room.id_ruangan).map(|c| c.id_jadwal).collect(),
+                deskripsi: format!(
+                    "Ruangan {} terpakai {} menit per minggu, selisih {} jam dari rata-rata {} menit.",
+                    room.id_ruangan, room.menit_terpakai, deviation, total / n
+                ),
+            });
+        }
+
+        result
+    }
+
+    // Soft objective: penalizes idle time between consecutive courses of a class on one day
+    pub fn check_kelas_idle_gaps(&self, schedule: &[OptimizedCourse]) -> ConstraintResult {
+        let Some(weight) = self.config.bobot_jeda_kelas else {
+            return ConstraintResult::default();
+        };
+
+        self.idle_gaps(
+            schedule,
+            weight,
+            0,
+            |c| (c.prodi, c.semester, c.id_kelas, c.id_waktu, c.hari),
+            |c| format!("kelas {} semester {} prodi {}", c.id_kelas, c.semester, c.prodi),
+        )
+    }
+
+    // Soft objective: penalizes a lecturer's waiting time between sessions beyond the tolerance
+    pub fn check_dosen_idle_gaps(&self, schedule: &[OptimizedCourse]) -> ConstraintResult {
+        let Some(weight) = self.config.bobot_jeda_dosen else {
+            return ConstraintResult::default();
+        };
+
+        self.idle_gaps(
+            schedule,
+            weight,
+            self.config.toleransi_jeda_dosen.unwrap_or(0),
+            |c| (c.id_dosen, c.hari),
+            |c| format!("dosen {}", c.id_dosen),
+        )
+    }
+
+    // Soft objective: clusters a lecturer's sessions on as few campus days as their daily limit allows
+    pub fn check_teaching_days(&self, schedule: &[OptimizedCourse]) -> ConstraintResult {
+        let mut per_dosen: HashMap<u32, (u32, Vec<u32>, Vec<u32>)> = HashMap::new();
+        for course in schedule {
+            let entry = per_dosen.entry(course.id_dosen).or_default();
+            entry.0 += course.sks;
+            if !entry.1.contains(&course.hari) {
+                entry.1.push(course.hari);
+            }
+            entry.2.push(course.id_jadwal);
+        }
+
+        let mut result = ConstraintResult::default();
+        for (id_dosen, (total_sks, days, id_jadwal)) in per_dosen {
+            let weight = self
+                .time_preferences
+                .get(&id_dosen)
+                .and_then(|p| p.bobot_hari_mengajar)
+                .or(self.config.bobot_hari_dosen);
+            let Some(weight) = weight else {
+                continue;
+            };
+
+            let min_days = self.config.max_sks_dosen_per_hari.map_or(1, |max| total_sks.div_ceil(max.max(1)));
+            let count = days.len() as u32;
+            if count <= min_days {
+                continue;
+            }
+
+            result.penalty += (count - min_days) * weight;
+            result.violations.push(ConstraintMessage {
+                id_jadwal,
+                deskripsi: format!(
+                    "Dosen {} datang {} hari per minggu, padahal cukup {} hari.",
+                    id_dosen, count, min_days
+                ),
+            });
+        }
+
+        result
+    }
+
+    // Soft objective: penalizes courses moved away from their day/time in the previous schedule
+    pub fn check_stability(&self, schedule: &[OptimizedCourse]) -> ConstraintResult {
+        let Some(weight) = self.config.bobot_stabilitas else {
+            return ConstraintResult::default();
+        };
+
+        let previous: HashMap<(u32, u32), (u32, u32)> = self
+            .config
+            .jadwal_sebelumnya
+            .iter()
+            .map(|p| ((p.id_jadwal, p.pertemuan), (p.hari, p.jam_mulai)))
+            .collect();
+
+        let violations: Vec<ConstraintMessage> = schedule
+            .iter()
+            .filter_map(|course| {
+                let &(hari, jam_mulai) = previous.get(&(course.id_jadwal, course.pertemuan))?;
+                (hari != course.hari || jam_mulai != course.jam_mulai).then(|| ConstraintMessage {
+                    id_jadwal: vec![course.id_jadwal],
+                    deskripsi: format!(
+                        "Jadwal {} berpindah dari {} {} ke {} {}.",
+                        course.id_jadwal,
+                        nama_hari(hari),
+                        format_jam(jam_mulai),
+                        nama_hari(course.hari),
+                        format_jam(course.jam_mulai)
+                    ),
+                })
+            })
+            .collect();
+
+        ConstraintResult {
+            penalty: violations.len() as u32 * weight,
+            violations,
+        }
+    }
+
+    // Idle minutes between consecutive sessions sharing a key, blocked intervals excluded.
+    // Only the part of a gap above `tolerance` is penalized.
+    fn idle_gaps<K: Eq + Hash>(
+        &self,
+        schedule: &[OptimizedCourse],
+        weight: u32,
+        tolerance: u32,
+        key: impl Fn(&OptimizedCourse) -> K,
+        subject: impl Fn(&OptimizedCourse) -> String,
+    ) -> ConstraintResult {
+        let mut result = ConstraintResult::default();
+        for (a, b) in Self::consecutive_sessions(schedule, key) {
+            if b.jam_mulai <= a.jam_akhir {
+                continue;
+            }
+
+            let blocked: u32 = self
+                .config
+                .blocked_ranges(a.hari)
+                .iter()
+                .map(|r| r.akhir.min(b.jam_mulai).saturating_sub(r.mulai.max(a.jam_akhir)))
+                .sum();
+            let gap = (b.jam_mulai - a.jam_akhir).saturating_sub(blocked);
+            if gap <= tolerance {
+                continue;
+            }
+
+            result.penalty += (gap - tolerance) * weight / 60;
+            result.violations.push(ConstraintMessage {
+                id_jadwal: vec![a.id_jadwal, b.id_jadwal],
+                deskripsi: format!(
+                    "Jeda {} menit untuk {} antara jadwal {} dan {}.",
+                    gap, subject(a), a.id_jadwal, b.id_jadwal
+                ),
+            });
+        }
+
+        result
+    }
+
+    // Penalizes moving between buildings without the configured travel time,
+    // for both the lecturer and the class
+    pub fn check_building_travel(&self, schedule: &[OptimizedCourse]) -> ConstraintResult {
+        let Some(min_gap) = self.config.jeda_pindah_gedung else {
+            return ConstraintResult::default();
+        };
+
+        let by_dosen = Self::consecutive_sessions(schedule, |c| (c.id_dosen, c.hari));
+        let by_kelas = Self::consecutive_sessions(schedule, |c| (c.prodi, c.semester, c.id_kelas, c.id_waktu, c.hari));
+
+        let violations: Vec<ConstraintMessage> = by_dosen
+            .into_iter()
+            .map(|pair| ("dosen", pair))
+            .chain(by_kelas.into_iter().map(|pair| ("kelas", pair)))
+            .filter_map(|(subject, (a, b))| {
+                let (gedung_a, gedung_b) = (self.config.gedung(a.ruangan)?, self.config.gedung(b.ruangan)?);
+                let gap = b.jam_mulai.saturating_sub(a.jam_akhir);
+                (gedung_a != gedung_b && gap < min_gap).then(|| ConstraintMessage {
+                    id_jadwal: vec![a.id_jadwal, b.id_jadwal],
+                    deskripsi: format!(
+                        "Jeda {} menit tidak cukup bagi {} untuk pindah dari gedung {} ke gedung {} (jadwal {} ke {}).",
+                        gap, subject, gedung_a, gedung_b, a.id_jadwal, b.id_jadwal
+                    ),
+                })
+            })
+            .collect();
+
+        ConstraintResult {
+            penalty: violations.len() as u32 * self.weights.building_travel,
+            violations,
+        }
+    }
+
+    // Consecutive sessions of a lecturer need a transition gap; overlaps are already conflicts.
+    // The decoder leaves the gap within a class, this catches sessions of different classes.
+    pub fn check_dosen_breaks(&self, schedule: &[OptimizedCourse]) -> ConstraintResult {
+        let violations: Vec<ConstraintMessage> = self
+            .short_breaks(schedule)
+            .into_iter()
+            .map(|(a, b, deskripsi)| ConstraintMessage { id_jadwal: vec![a.id_jadwal, b.id_jadwal], deskripsi })
+            .collect();
+
+        ConstraintResult {
+            penalty: violations.len() as u32 * self.weights.dosen_break,
+            violations,
+        }
+    }
+
+    // Consecutive sessions of a lecturer with less than jeda_min_dosen in between, with the reason
+    fn short_breaks<'a>(&self, schedule: &'a [OptimizedCourse]) -> Vec<(&'a OptimizedCourse, &'a OptimizedCourse, String)> {
+        let Some(min_gap) = self.config.jeda_min_dosen else {
+            return vec![];
+        };
+
+        Self::consecutive_sessions(schedule, |c| (c.id_dosen, c.hari))
+            .into_iter()
+            .filter(|(a, b)| b.jam_mulai >= a.jam_akhir && b.jam_mulai - a.jam_akhir < min_gap)
+            .map(|(a, b)| {
+                let deskripsi = format!(
+                    "Dosen {} hanya punya jeda {} menit antara jadwal {} dan {} pada hari {} (minimal {} menit).",
+                    a.id_dosen,
+                    b.jam_mulai - a.jam_akhir,
+                    a.id_jadwal,
+                    b.id_jadwal,
+                    nama_hari(a.hari),
+                    min_gap
+                );
+                (a, b, deskripsi)
             })
+            .collect()
+    }
+
+    // Flags courses whose concrete meeting dates in the semester fall on a lecturer's absence
+    pub fn check_absences(&self, schedule: &[OptimizedCourse]) -> ConstraintResult {
+        let Some(kalender) = self.config.kalender else {
+            return ConstraintResult::default();
+        };
+
+        let mut result = ConstraintResult::default();
+        for course in schedule {
+            let Some(pref) = self.time_preferences.get(&course.id_dosen).filter(|p| !p.absen.is_empty()) else {
+                continue;
+            };
+
+            let collisions: Vec<NaiveDate> = meeting_dates(&kalender, course.hari)
+                .filter(|date| pref.absen.iter().any(|(mulai, akhir)| mulai <= date && date <= akhir))
+                .collect();
+            if collisions.is_empty() {
+                continue;
+            }
+
+            result.penalty += collisions.len() as u32 * self.weights.absence;
+            result.violations.push(ConstraintMessage {
+                id_jadwal: vec![course.id_jadwal],
+                deskripsi: format!(
+                    "Dosen {} berhalangan pada {} pertemuan jadwal {}: {}.",
+                    course.id_dosen,
+                    collisions.len(),
+                    course.id_jadwal,
+                    collisions.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(", ")
+                ),
+            });
+        }
+
+        result
+    }
+
+    // Pairs of sessions sharing a key that directly follow each other in time
+    fn consecutive_sessions<K: Eq + Hash>(
+        schedule: &[OptimizedCourse],
+        key: impl Fn(&OptimizedCourse) -> K,
+    ) -> Vec<(&OptimizedCourse, &OptimizedCourse)> {
+        let mut groups: HashMap<K, Vec<&OptimizedCourse>> = HashMap::new();
+        for course in schedule {
+            groups.entry(key(course)).or_default().push(course);
+        }
+
+        groups
+            .into_values()
+            .flat_map(|mut courses| {
+                courses.sort_by_key(|c| c.jam_mulai);
+                courses.windows(2).map(|pair| (pair[0], pair[1])).collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    pub fn check_preferences(&self, schedule: &[OptimizedCourse]) -> PreferenceResult {
+        let mut violations: Vec<PreferenceMessage> = schedule.par_iter()
+            .filter_map(|course| self.course_preference(course))
             .collect();
 
+        violations.extend(self.check_back_to_back(schedule));
+        let penalty = violations.iter().map(|v| v.penalty).sum();
+
         PreferenceResult {
-            penalty: (violations.len() as u32) * 100,
+            penalty,
             violations,
         }
     }
 
-    #[inline]
+    // The lecturer's preference for the slot of one course; None when it is preferred
+    fn course_preference(&self, course: &OptimizedCourse) -> Option<PreferenceMessage> {
+        let pref = self.time_preferences.get(&course.id_dosen)?;
+
+        let (weight, keterangan) = match pref.level(course.hari, course.jam_mulai, course.jam_akhir) {
+            PreferenceLevel::Prefer => return None,
+            PreferenceLevel::Neutral => (self.weights.neutral_preference, "kurang prefer"),
+            PreferenceLevel::Avoid => (self.weights.preference, "menghindari"),
+        };
+
+        Some(PreferenceMessage {
+            id_jadwal: course.id_jadwal,
+            id_dosen: course.id_dosen,
+            hari: course.hari,
+            jam_mulai: course.jam_mulai,
+            penalty: weight * pref.prioritas,
+            deskripsi: format!(
+                "Dosen {} {} jadwal {} {}-{}.",
+                course.id_dosen,
+                keterangan,
+                nama_hari(course.hari),
+                format_jam(course.jam_mulai),
+                format_jam(course.jam_akhir)
+            ),
+        })
+    }
+
+    // Checks each lecturer's same-day sessions against their back-to-back preference
+    fn check_back_to_back(&self, schedule: &[OptimizedCourse]) -> Vec<PreferenceMessage> {
+        self.back_to_back_pairs(schedule)
+            .into_iter()
+            .map(|(a, b, deskripsi)| PreferenceMessage {
+                id_jadwal: b.id_jadwal,
+                id_dosen: a.id_dosen,
+                hari: b.hari,
+                jam_mulai: b.jam_mulai,
+                penalty: self.weights.back_to_back * self.time_preferences[&a.id_dosen].prioritas,
+                deskripsi,
+            })
+            .collect()
+    }
+
+    // Same-day session pairs going against their lecturer's back-to-back preference, with the reason
+    fn back_to_back_pairs<'a>(&self, schedule: &'a [OptimizedCourse]) -> Vec<(&'a OptimizedCourse, &'a OptimizedCourse, String)> {
+        let mut per_day: HashMap<(u32, u32), Vec<&OptimizedCourse>> = HashMap::new();
+        for course in schedule {
+            let wants = self.time_preferences.get(&course.id_dosen).and_then(|p| p.back_to_back);
+            if wants.is_some() {
+                per_day.entry((course.id_dosen, course.hari)).or_default().push(course);
+            }
+        }
+
+        let mut pairs = Vec::new();
+        for ((id_dosen, _), mut courses) in per_day {
+            let pref = &self.time_preferences[&id_dosen];
+            let Some(mode) = pref.back_to_back else {
+                continue;
+            };
+            courses.sort_by_key(|c| c.jam_mulai);
+
+            for pair in courses.windows(2) {
+                let (a, b) = (pair[0], pair[1]);
+                let consecutive = a.jam_akhir == b.jam_mulai;
+                let deskripsi = match mode {
+                    BackToBack::Require if !consecutive => format!(
+                        "Dosen {} ingin sesi berurutan, tetapi ada jeda antara jadwal {} dan {}.",
+                        id_dosen, a.id_jadwal, b.id_jadwal
+                    ),
+                    BackToBack::Forbid if consecutive => format!(
+                        "Dosen {} tidak ingin sesi berurutan, tetapi jadwal {} langsung disusul jadwal {}.",
+                        id_dosen, a.id_jadwal, b.id_jadwal
+                    ),
+                    _ => continue,
+                };
+
+                pairs.push((a, b, deskripsi));
+            }
+        }
+
+        pairs
+    }
+
+    // Overlapping pairs among courses sharing a key, courses without a key are skipped
+    fn overlapping_pairs<'a, K: Eq + Hash>(
+        &self,
+        schedule: &'a [OptimizedCourse],
+        key: impl Fn(&OptimizedCourse) -> Option<K>,
+    ) -> Vec<(&'a OptimizedCourse, &'a OptimizedCourse)> {
+        let mut buckets: HashMap<K, Vec<&OptimizedCourse>> = HashMap::new();
+        for course in schedule {
+            if let Some(k) = key(course) {
+                buckets.entry(k).or_default().push(course);
+            }
+        }
+
+        if schedule.len() < self.parallel_conflicts_from {
+            return buckets
+                .into_values()
+                .flat_map(|courses| IntervalIndex::new(courses).overlapping_pairs())
+                .collect();
+        }
+
+        buckets
+            .into_values()
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .flat_map_iter(|courses| IntervalIndex::new(courses).overlapping_pairs())
+            .collect()
+    }
+
     fn is_overlap(a: &OptimizedCourse, b: &OptimizedCourse) -> bool {
         a.jam_mulai < b.jam_akhir && b.jam_mulai < a.jam_akhir
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id_jadwal: u32, id_kelas: u32, jam_mulai: u32) -> OptimizedCourse {
+        OptimizedCourse {
+            id_jadwal,
+            id_matkul: id_jadwal,
+            id_dosen: 7,
+            id_kelas,
+            id_waktu: 1,
+            hari: 1,
+            jam_mulai,
+            jam_akhir: jam_mulai + 80,
+            ruangan: 0,
+            semester: 1,
+            sks: 2,
+            prodi: 1,
+            is_online: false,
+            pertemuan: 0,
+        }
+    }
+
+    #[test]
+    fn short_lecturer_break_is_a_soft_conflict() {
+        let config = ConstraintConfig { jeda_min_dosen: Some(20), ..ConstraintConfig::default() };
+        let checker = ScheduleChecker::new(vec![], config, PenaltyWeights::default());
+        let schedule = [session(1, 1, 480), session(2, 2, 560)];
+
+        let conflicts = checker.detect_conflicts(&schedule).conflicts;
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!((conflicts[0].jenis, conflicts[0].severity), (ConflictType::Jeda, Severity::Soft));
+
+        let evaluation = checker.evaluate(&schedule);
+        assert_eq!(evaluation.hard_violations, 0);
+        assert_eq!(evaluation.soft_penalty, PenaltyWeights::default().dosen_break as f64);
+    }
+}
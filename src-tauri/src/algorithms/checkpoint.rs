@@ -0,0 +1,67 @@
+use std::fs;
+use std::path::PathBuf;
+
+use super::models::{Checkpoint, SwarmState};
+
+/// Checkpoints of unfinished jobs, one JSON file per job overwritten on every save
+pub struct CheckpointStore {
+    dir: PathBuf,
+}
+
+impl CheckpointStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn save(&self, checkpoint: &Checkpoint, swarm: &SwarmState) -> Result<(), String> {
+        fs::create_dir_all(&self.dir).map_err(|e| format!("Gagal membuat folder checkpoint: {}", e))?;
+        let json = serde_json::to_string(&(checkpoint, swarm)).map_err(|e| e.to_string())?;
+
+        // Written next to the target first, so closing the app mid-write keeps the previous checkpoint
+        let path = self.path(&checkpoint.id)?;
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, json).map_err(|e| format!("Gagal menyimpan checkpoint: {}", e))?;
+        fs::rename(&tmp, &path).map_err(|e| format!("Gagal menyimpan checkpoint: {}", e))
+    }
+
+    /// The given checkpoint, or the most recently saved one
+    pub fn load(&self, id: Option<&str>) -> Result<(Checkpoint, SwarmState), String> {
+        let id = match id {
+            Some(id) => id.to_string(),
+            None => self.latest()?.ok_or("Tidak ada checkpoint yang tersimpan")?,
+        };
+        let json = fs::read_to_string(self.path(&id)?).map_err(|_| format!("Checkpoint {} tidak ditemukan", id))?;
+        serde_json::from_str(&json).map_err(|e| format!("Checkpoint {} rusak: {}", id, e))
+    }
+
+    pub fn remove(&self, id: &str) -> Result<(), String> {
+        match fs::remove_file(self.path(id)?) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Gagal menghapus checkpoint: {}", e)),
+            _ => Ok(()),
+        }
+    }
+
+    fn latest(&self) -> Result<Option<String>, String> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Gagal membaca folder checkpoint: {}", e)),
+        };
+
+        Ok(entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| Some((fs::metadata(&path).ok()?.modified().ok()?, path.file_stem()?.to_str()?.to_string())))
+            .max()
+            .map(|(_, id)| id))
+    }
+
+    // Ids come from the frontend, so nothing that could leave the folder
+    fn path(&self, id: &str) -> Result<PathBuf, String> {
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(format!("Id checkpoint tidak valid: {}", id));
+        }
+        Ok(self.dir.join(format!("{}.json", id)))
+    }
+}
@@ -0,0 +1,78 @@
+use std::fmt;
+
+use serde::Serialize;
+
+/// Error of the Tauri commands, tagged by `kind` so the frontend can branch on it
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScheduleError {
+    /// An input row that could not be read. `row` is the line as shown in a spreadsheet
+    /// (header = 1), `column` the header of the offending field when known
+    ParseError {
+        row: Option<u64>,
+        column: Option<String>,
+        message: String,
+    },
+    InvalidParams {
+        message: String,
+    },
+    /// Stopped before anything could be returned
+    Stopped,
+    /// No room to take more work right now, e.g. the server's job queue is full
+    Busy {
+        message: String,
+    },
+    Internal {
+        message: String,
+    },
+}
+
+impl ScheduleError {
+    pub fn invalid(message: impl Into<String>) -> Self {
+        ScheduleError::InvalidParams { message: message.into() }
+    }
+
+    /// `file` names the input in the message, e.g. "Course CSV"
+    pub fn csv(file: &str, error: &csv::Error, headers: Option<&csv::StringRecord>) -> Self {
+        let column = match error.kind() {
+            csv::ErrorKind::Deserialize { err, .. } => {
+                err.field().and_then(|field| headers?.get(field as usize)).map(str::to_string)
+            }
+            _ => None,
+        };
+
+        ScheduleError::ParseError {
+            row: error.position().map(|pos| pos.line()),
+            column,
+            message: format!("{} error: {}", file, error),
+        }
+    }
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScheduleError::ParseError { message, .. }
+            | ScheduleError::InvalidParams { message }
+            | ScheduleError::Busy { message }
+            | ScheduleError::Internal { message } => f.write_str(message),
+            ScheduleError::Stopped => f.write_str("Proses dihentikan sebelum ada hasil"),
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+// Helpers that still report plain messages (file access, exports) are internal errors
+impl From<String> for ScheduleError {
+    fn from(message: String) -> Self {
+        ScheduleError::Internal { message }
+    }
+}
+
+impl From<&str> for ScheduleError {
+    fn from(message: &str) -> Self {
+        ScheduleError::Internal { message: message.to_string() }
+    }
+}
@@ -0,0 +1,350 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use chrono::{NaiveDate, Utc};
+use rust_xlsxwriter::{Color, Format, FormatAlign, FormatBorder, Workbook, XlsxError};
+
+use super::checker::{format_jam, meeting_dates, nama_hari};
+use super::models::{OptimizedCourse, SemesterCalendar, SLOT_MINUTES};
+
+const HEADERS: [(&str, f64); 9] = [
+    ("Hari", 10.0),
+    ("Jam Mulai", 10.0),
+    ("Jam Akhir", 10.0),
+    ("ID Jadwal", 10.0),
+    ("ID Matkul", 10.0),
+    ("ID Dosen", 10.0),
+    ("Ruangan", 10.0),
+    ("SKS", 6.0),
+    ("Keterangan", 16.0),
+];
+
+/// Writes the schedule to an .xlsx file, one sheet per class sorted by day and start time
+pub fn export_schedule_xlsx(schedule: &[OptimizedCourse], path: &str) -> Result<(), XlsxError> {
+    let mut workbook = Workbook::new();
+
+    let title = Format::new()
+        .set_bold()
+        .set_font_size(13)
+        .set_align(FormatAlign::Center)
+        .set_align(FormatAlign::VerticalCenter);
+    let header = Format::new()
+        .set_bold()
+        .set_align(FormatAlign::Center)
+        .set_background_color(Color::RGB(0xD9E1F2))
+        .set_border(FormatBorder::Thin);
+    let cell = Format::new().set_border(FormatBorder::Thin);
+
+    let mut classes: BTreeMap<(u32, u32, u32), Vec<&OptimizedCourse>> = BTreeMap::new();
+    for course in schedule {
+        classes.entry((course.prodi, course.semester, course.id_kelas)).or_default().push(course);
+    }
+
+    for ((prodi, semester, id_kelas), mut courses) in classes {
+        courses.sort_by_key(|c| (c.hari, c.jam_mulai, c.id_jadwal));
+
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(format!("P{} S{} K{}", prodi, semester, id_kelas))?;
+
+        let last_col = HEADERS.len() as u16 - 1;
+        worksheet.merge_range(
+            0,
+            0,
+            0,
+            last_col,
+            &format!("Jadwal Prodi {} - Semester {} - Kelas {}", prodi, semester, id_kelas),
+            &title,
+        )?;
+        worksheet.set_row_height(0, 22)?;
+
+        for (col, (name, width)) in HEADERS.iter().enumerate() {
+            worksheet.write_with_format(1, col as u16, *name, &header)?;
+            worksheet.set_column_width(col as u16, *width)?;
+        }
+
+        for (i, course) in courses.iter().enumerate() {
+            let row = i as u32 + 2;
+            let ruangan = if course.is_online { "Online".to_string() } else { course.ruangan.to_string() };
+            let keterangan = match course.pertemuan {
+                0 => String::new(),
+                n => format!("Pertemuan {}", n),
+            };
+
+            worksheet.write_with_format(row, 0, nama_hari(course.hari), &cell)?;
+            worksheet.write_with_format(row, 1, format_jam(course.jam_mulai), &cell)?;
+            worksheet.write_with_format(row, 2, format_jam(course.jam_akhir), &cell)?;
+            worksheet.write_with_format(row, 3, course.id_jadwal, &cell)?;
+            worksheet.write_with_format(row, 4, course.id_matkul, &cell)?;
+            worksheet.write_with_format(row, 5, course.id_dosen, &cell)?;
+            worksheet.write_with_format(row, 6, ruangan, &cell)?;
+            worksheet.write_with_format(row, 7, course.sks, &cell)?;
+            worksheet.write_with_format(row, 8, keterangan, &cell)?;
+        }
+
+        worksheet.set_freeze_panes(2, 0)?;
+    }
+
+    // An empty workbook cannot be opened by Excel
+    if schedule.is_empty() {
+        workbook.add_worksheet().set_name("Jadwal")?;
+    }
+
+    workbook.save(path)
+}
+
+/// Output formats of `write_schedule`, picked by file extension
+pub const SCHEDULE_FORMATS: [&str; 3] = ["csv", "json", "xlsx"];
+
+/// Writes the schedule as .csv or .json (the same rows evaluate_schedule reads back) or as .xlsx,
+/// depending on the extension of `path`
+pub fn write_schedule(schedule: &[OptimizedCourse], path: &Path) -> Result<(), String> {
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+    match extension.as_str() {
+        "csv" => {
+            let mut writer = csv::Writer::from_path(path).map_err(|e| format!("Gagal menulis {}: {}", path.display(), e))?;
+            for course in schedule {
+                writer.serialize(course).map_err(|e| e.to_string())?;
+            }
+            writer.flush().map_err(|e| format!("Gagal menulis {}: {}", path.display(), e))
+        }
+        "json" => {
+            let json = serde_json::to_string_pretty(schedule).map_err(|e| e.to_string())?;
+            fs::write(path, json).map_err(|e| format!("Gagal menulis {}: {}", path.display(), e))
+        }
+        "xlsx" => export_schedule_xlsx(schedule, &path.to_string_lossy()).map_err(|e| format!("Excel export error: {}", e)),
+        _ => Err(format!("Format output tidak dikenal: {} (pakai .csv, .json atau .xlsx)", path.display())),
+    }
+}
+
+/// Writes one .ics per dosen and per kelas into `dir`, each course a weekly event for the
+/// semester. Times are floating (no time zone) so calendars show them as local time.
+/// Returns the paths of the written files.
+pub fn export_ics(schedule: &[OptimizedCourse], kalender: &SemesterCalendar, dir: &Path) -> Result<Vec<String>, String> {
+    let mut files: BTreeMap<String, Vec<&OptimizedCourse>> = BTreeMap::new();
+    for course in schedule {
+        files.entry(format!("dosen_{}.ics", course.id_dosen)).or_default().push(course);
+        files
+            .entry(format!("kelas_{}_{}_{}.ics", course.prodi, course.semester, course.id_kelas))
+            .or_default()
+            .push(course);
+    }
+
+    fs::create_dir_all(dir).map_err(|e| format!("Gagal membuat folder {}: {}", dir.display(), e))?;
+    let dtstamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+    let mut written = Vec::with_capacity(files.len());
+    for (name, courses) in files {
+        let mut ics = String::from("BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//schedule-optimization//Jadwal Kuliah//ID\r\nCALSCALE:GREGORIAN\r\n");
+        for course in courses {
+            let dates: Vec<NaiveDate> = meeting_dates(kalender, course.hari).collect();
+            let Some(first) = dates.first() else {
+                continue;
+            };
+            ics.push_str(&vevent(course, *first, dates.len(), &dtstamp));
+        }
+        ics.push_str("END:VCALENDAR\r\n");
+
+        let path = dir.join(&name);
+        fs::write(&path, ics).map_err(|e| format!("Gagal menulis {}: {}", path.display(), e))?;
+        written.push(path.display().to_string());
+    }
+
+    Ok(written)
+}
+
+fn vevent(course: &OptimizedCourse, first: NaiveDate, count: usize, dtstamp: &str) -> String {
+    let at = |menit: u32| format!("{}T{:02}{:02}00", first.format("%Y%m%d"), menit / 60, menit % 60);
+    let lokasi = if course.is_online { "Online".to_string() } else { format!("Ruangan {}", course.ruangan) };
+    let summary = match course.pertemuan {
+        0 => format!("Matkul {}", course.id_matkul),
+        n => format!("Matkul {} (pertemuan {})", course.id_matkul, n),
+    };
+
+    format!(
+        "BEGIN:VEVENT\r\nUID:{}-{}@schedule-optimization\r\nDTSTAMP:{}\r\nDTSTART:{}\r\nDTEND:{}\r\n\
+         RRULE:FREQ=WEEKLY;COUNT={}\r\nSUMMARY:{}\r\nLOCATION:{}\r\nDESCRIPTION:{}\r\nEND:VEVENT\r\n",
+        course.id_jadwal,
+        course.pertemuan,
+        dtstamp,
+        at(course.jam_mulai),
+        at(course.jam_akhir),
+        count,
+        escape_text(&summary),
+        escape_text(&lokasi),
+        escape_text(&format!(
+            "Dosen {}, Prodi {}, Semester {}, Kelas {}, {} SKS",
+            course.id_dosen, course.prodi, course.semester, course.id_kelas, course.sks
+        )),
+    )
+}
+
+// RFC 5545 TEXT escaping
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\").replace(';', "\\;").replace(',', "\\,")
+}
+
+// A4 landscape in PDF points
+const PAGE_WIDTH: f64 = 842.0;
+const PAGE_HEIGHT: f64 = 595.0;
+const MARGIN: f64 = 30.0;
+const TIME_COLUMN: f64 = 45.0;
+
+/// Writes a printable weekly grid to a PDF, one page per class followed by one page per dosen.
+/// Uses only the built-in Helvetica fonts, so no font files are needed.
+pub fn export_pdf(schedule: &[OptimizedCourse], path: &Path) -> Result<(), String> {
+    let mut pages: Vec<(String, Vec<&OptimizedCourse>)> = Vec::new();
+
+    let mut classes: BTreeMap<(u32, u32, u32), Vec<&OptimizedCourse>> = BTreeMap::new();
+    let mut lecturers: BTreeMap<u32, Vec<&OptimizedCourse>> = BTreeMap::new();
+    for course in schedule {
+        classes.entry((course.prodi, course.semester, course.id_kelas)).or_default().push(course);
+        lecturers.entry(course.id_dosen).or_default().push(course);
+    }
+    pages.extend(classes.into_iter().map(|((prodi, semester, id_kelas), courses)| {
+        (format!("Jadwal Prodi {} - Semester {} - Kelas {}", prodi, semester, id_kelas), courses)
+    }));
+    pages.extend(lecturers.into_iter().map(|(id_dosen, courses)| (format!("Jadwal Dosen {}", id_dosen), courses)));
+
+    // Same columns on every page: Senin-Jumat, plus the weekend if anything is scheduled then
+    let last_day = schedule.iter().map(|c| c.hari).filter(|h| (1..=7).contains(h)).max().unwrap_or(5).max(5);
+    let days: Vec<u32> = (1..=last_day).collect();
+
+    let streams: Vec<String> = pages.iter().map(|(title, courses)| timetable_page(title, courses, &days)).collect();
+    fs::write(path, pdf_document(&streams)).map_err(|e| format!("Gagal menulis {}: {}", path.display(), e))
+}
+
+fn timetable_page(title: &str, courses: &[&OptimizedCourse], days: &[u32]) -> String {
+    let start = courses.iter().map(|c| c.jam_mulai).min().unwrap_or(420) / SLOT_MINUTES * SLOT_MINUTES;
+    let end = courses.iter().map(|c| c.jam_akhir).max().unwrap_or(1020).div_ceil(SLOT_MINUTES) * SLOT_MINUTES;
+    let rows = ((end - start) / SLOT_MINUTES).max(1);
+
+    let top = PAGE_HEIGHT - MARGIN - 40.0;
+    let header_height = 18.0;
+    let row_height = ((top - header_height - MARGIN) / rows as f64).min(24.0);
+    let day_width = (PAGE_WIDTH - 2.0 * MARGIN - TIME_COLUMN) / days.len() as f64;
+    let grid_top = top - header_height;
+    let grid_bottom = grid_top - rows as f64 * row_height;
+    let y_of = |menit: u32| grid_top - (menit.saturating_sub(start)) as f64 / SLOT_MINUTES as f64 * row_height;
+
+    let mut ops = String::new();
+    ops.push_str(&text(MARGIN, PAGE_HEIGHT - MARGIN - 16.0, "F2", 16.0, title));
+
+    // Header and time labels
+    for (i, &hari) in days.iter().enumerate() {
+        let x = MARGIN + TIME_COLUMN + i as f64 * day_width;
+        ops.push_str(&text(x + 4.0, top - 13.0, "F2", 10.0, nama_hari(hari)));
+    }
+    for row in 0..rows {
+        let menit = start + row * SLOT_MINUTES;
+        ops.push_str(&text(MARGIN + 4.0, y_of(menit) - 9.0, "F1", 7.0, &format_jam(menit)));
+    }
+
+    // Grid
+    ops.push_str("0.5 w 0.6 G\n");
+    for row in 0..=rows {
+        let y = grid_top - row as f64 * row_height;
+        ops.push_str(&format!("{:.2} {:.2} m {:.2} {:.2} l S\n", MARGIN, y, PAGE_WIDTH - MARGIN, y));
+    }
+    for col in 0..=days.len() {
+        let x = MARGIN + TIME_COLUMN + col as f64 * day_width;
+        ops.push_str(&format!("{:.2} {:.2} m {:.2} {:.2} l S\n", x, top, x, grid_bottom));
+    }
+
+    // Course blocks, filled over the grid lines they span
+    for course in courses {
+        let Some(col) = days.iter().position(|&h| h == course.hari) else {
+            continue;
+        };
+        let x = MARGIN + TIME_COLUMN + col as f64 * day_width + 1.0;
+        let y_top = y_of(course.jam_mulai);
+        let height = y_top - y_of(course.jam_akhir);
+        ops.push_str(&format!(
+            "0.85 0.9 0.97 rg {:.2} {:.2} {:.2} {:.2} re f 0 g\n",
+            x, y_top - height, day_width - 2.0, height
+        ));
+
+        let ruangan = if course.is_online { "Online".to_string() } else { format!("R. {}", course.ruangan) };
+        let lines = [
+            (format!("Matkul {}", course.id_matkul), "F2"),
+            (format!("{} - {}", format_jam(course.jam_mulai), format_jam(course.jam_akhir)), "F1"),
+            (format!("Dosen {} | Kelas {}", course.id_dosen, course.id_kelas), "F1"),
+            (ruangan, "F1"),
+        ];
+        for (i, (line, font)) in lines.iter().enumerate() {
+            let y = y_top - 9.0 - i as f64 * 8.5;
+            if y < y_top - height + 2.0 {
+                break;
+            }
+            ops.push_str(&text(x + 3.0, y, font, 7.0, line));
+        }
+    }
+
+    // Outer border
+    ops.push_str(&format!(
+        "0 G 1 w {:.2} {:.2} {:.2} {:.2} re S\n",
+        MARGIN,
+        grid_bottom,
+        PAGE_WIDTH - 2.0 * MARGIN,
+        top - grid_bottom
+    ));
+
+    ops
+}
+
+fn text(x: f64, y: f64, font: &str, size: f64, value: &str) -> String {
+    let escaped: String = value
+        .chars()
+        .filter(|c| c.is_ascii())
+        .flat_map(|c| match c {
+            '(' | ')' | '\\' => vec!['\\', c],
+            _ => vec![c],
+        })
+        .collect();
+    format!("BT /{} {} Tf {:.2} {:.2} Td ({}) Tj ET\n", font, size, x, y, escaped)
+}
+
+// Minimal PDF 1.4: catalog, page tree, two standard fonts, then a page and content stream per page
+fn pdf_document(streams: &[String]) -> Vec<u8> {
+    let page_count = streams.len().max(1);
+    let mut objects: Vec<String> = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            (0..page_count).map(|i| format!("{} 0 R", 5 + i * 2)).collect::<Vec<_>>().join(" "),
+            page_count
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>".to_string(),
+    ];
+
+    let empty = String::new();
+    for i in 0..page_count {
+        let stream = streams.get(i).unwrap_or(&empty);
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+            PAGE_WIDTH,
+            PAGE_HEIGHT,
+            6 + i * 2
+        ));
+        objects.push(format!("<< /Length {} >>\nstream\n{}endstream", stream.len(), stream));
+    }
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", i + 1, object).as_bytes());
+    }
+
+    let xref = pdf.len();
+    pdf.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+    for offset in offsets {
+        pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    pdf.extend_from_slice(
+        format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref).as_bytes(),
+    );
+
+    pdf
+}
@@ -0,0 +1,941 @@
+// HTTP API for running the optimizer without the desktop app (feature "server").
+// Jobs use the same registry as the Tauri commands, only without progress events, and
+// are run by a fixed number of workers so a burst of requests cannot start unbounded runs.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{atomic::{AtomicBool, AtomicU64, Ordering}, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        ConnectInfo, Path, Query, Request, State,
+    },
+    http::{header::{AUTHORIZATION, RETRY_AFTER}, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{IntoParams, Modify, OpenApi, ToSchema};
+use utoipa_swagger_ui::SwaggerUi;
+
+use super::checker::{ConflictResult, EvaluationReport, PreferenceResult};
+use super::error::ScheduleError;
+use super::job_store::JobStore;
+use super::models::{
+    ConstraintConfig, CourseRequest, JobId, JobState, JobStatus, LecturerPreference, OptimizedCourse, PauseControl, PenaltyWeights,
+    PsoParameters, ScheduleChecker, PSO,
+};
+use crate::{finished_event, load_preferences, parse_course_csv, AppState, Job};
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct OptimizeRequest {
+    /// CSV with columns id,id_matkul,id_dosen,id_waktu,id_kelas,semester,sks,prodi
+    pub course_csv: String,
+    /// Lecturer time preferences, per hari_pagi/hari_malam column or per interval (id_dosen,hari,jam_mulai,jam_akhir)
+    pub preference_csv: String,
+    #[serde(default)]
+    pub lecturer_csv: Option<String>,
+    #[serde(default)]
+    pub absence_csv: Option<String>,
+    pub params: PsoParameters,
+}
+
+// A hand-edited schedule to check against the preferences it was made for
+#[derive(Deserialize, ToSchema)]
+pub struct ValidateRequest {
+    pub schedule: Vec<OptimizedCourse>,
+    pub preference_csv: String,
+    #[serde(default)]
+    pub lecturer_csv: Option<String>,
+    #[serde(default)]
+    pub absence_csv: Option<String>,
+    #[serde(default)]
+    pub constraints: ConstraintConfig,
+    #[serde(default)]
+    pub penalty_weights: PenaltyWeights,
+}
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct JobQuery {
+    #[schema(value_type = u64)]
+    #[param(value_type = u64)]
+    pub job_id: JobId,
+}
+
+// Control messages a client can send over /ws, e.g. {"command": "pause"}
+#[derive(Deserialize, ToSchema)]
+#[serde(tag = "command", rename_all = "lowercase")]
+pub enum JobCommand {
+    Stop,
+    Pause,
+    Resume,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct JobCreated {
+    #[schema(value_type = u64)]
+    pub job_id: JobId,
+}
+
+// Result of a finished job, also found in JobStatus.result
+#[derive(Serialize, ToSchema)]
+pub struct OptimizeResult {
+    #[schema(value_type = u64)]
+    pub job_id: JobId,
+    pub success: bool,
+    pub fitness: f64,
+    pub is_feasible: bool,
+    pub hard_violations: u32,
+    pub soft_penalty: f64,
+    pub message: EvaluationReport,
+    pub stopped_early: bool,
+    pub schedule: Vec<OptimizedCourse>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ValidateResult {
+    pub fitness: f64,
+    pub is_feasible: bool,
+    pub hard_violations: u32,
+    pub soft_penalty: f64,
+    pub conflicts: ConflictResult,
+    pub preferences: PreferenceResult,
+    pub message: EvaluationReport,
+}
+
+pub struct ServerConfig {
+    pub workers: usize,     // Jobs optimized at the same time
+    pub queue_size: usize,  // Jobs waiting for a worker before /optimize answers busy
+    pub api_keys: Vec<String>,  // Empty leaves every route open
+    pub result_ttl: Duration,   // How long a finished job and its result stay retrievable
+    pub requests_per_minute: u32,   // /optimize calls per client per minute, 0 = unlimited
+    pub max_jobs_per_client: usize, // Queued or running jobs per client, 0 = unlimited
+    pub database: Option<PathBuf>,  // SQLite file keeping jobs across restarts, none = memory only
+}
+
+impl Default for ServerConfig {
+    // One run already uses every core for the particles, so by default jobs take turns
+    fn default() -> Self {
+        Self {
+            workers: 1,
+            queue_size: 16,
+            api_keys: Vec::new(),
+            result_ttl: Duration::from_secs(24 * 60 * 60),
+            requests_per_minute: 10,
+            max_jobs_per_client: 2,
+            database: None,
+        }
+    }
+}
+
+pub struct ServerState {
+    pub jobs: Arc<AppState>,
+    queue: mpsc::Sender<QueuedJob>,
+    api_keys: Vec<String>,
+    metrics: Arc<Metrics>,
+    requests_per_minute: u32,
+    max_jobs_per_client: usize,
+    // Client of every queued or running job, removed once the job is done
+    job_clients: Arc<Mutex<HashMap<JobId, String>>>,
+    // Start of each client's current rate window and the requests made in it
+    rate_windows: Mutex<HashMap<String, (Instant, u32)>>,
+    store: Option<Arc<JobStore>>,
+    // Set once the server is going down; workers stop taking jobs and /optimize refuses new ones
+    shutdown: watch::Sender<bool>,
+    workers: Mutex<Vec<JoinHandle<()>>>,
+}
+
+// Who a request counts against: its API key, or its address without keys
+#[derive(Clone)]
+struct Client(String);
+
+// Counters behind /metrics; the gauges are read from the job registry when scraped
+#[derive(Default)]
+struct Metrics {
+    finished_total: AtomicU64,
+    stopped_total: AtomicU64,
+    failed_total: AtomicU64,
+    // Iterations of jobs that are done; running jobs are added at scrape time
+    iterations_done: AtomicU64,
+}
+
+struct QueuedJob {
+    job: Arc<Job>,
+    courses: Vec<CourseRequest>,
+    time_preferences: Vec<LecturerPreference>,
+    params: PsoParameters,
+}
+
+impl ServerState {
+    /// Starts the workers and picks up the jobs left in the database; must be called
+    /// inside a tokio runtime
+    pub fn new(config: ServerConfig) -> std::io::Result<Self> {
+        let (queue, receiver) = mpsc::channel::<QueuedJob>(config.queue_size.max(1));
+        let receiver = Arc::new(tokio::sync::Mutex::new(receiver));
+        let metrics = Arc::new(Metrics::default());
+        let job_clients: Arc<Mutex<HashMap<JobId, String>>> = Arc::default();
+        let store = config
+            .database
+            .as_deref()
+            .map(|path| JobStore::open(path).map(Arc::new).map_err(std::io::Error::other))
+            .transpose()?;
+
+        let (shutdown, _) = watch::channel(false);
+        let workers = (0..config.workers.max(1))
+            .map(|_| {
+                let receiver = receiver.clone();
+                let metrics = metrics.clone();
+                let job_clients = job_clients.clone();
+                let store = store.clone();
+                let mut shutdown = shutdown.subscribe();
+                tokio::spawn(async move {
+                    loop {
+                        // The lock is only held while waiting, so idle workers take turns receiving
+                        let queued = tokio::select! {
+                            biased;
+                            _ = shutdown.wait_for(|stopping| *stopping) => None,
+                            queued = async { receiver.lock().await.recv().await } => queued,
+                        };
+                        // A job taken during shutdown stays queued in the database for the next start
+                        let Some(queued) = queued.filter(|_| !*shutdown.borrow()) else {
+                            break;
+                        };
+                        let job_id = queued.job.id;
+                        process(queued, &metrics, store.as_deref()).await;
+                        job_clients.lock().unwrap().remove(&job_id);
+                    }
+                })
+            })
+            .collect();
+
+        let jobs = Arc::new(AppState::default());
+        if let Some(store) = &store {
+            restore_jobs(store, &jobs, &job_clients, queue.clone()).map_err(std::io::Error::other)?;
+        }
+        tokio::spawn(expire_results(jobs.clone(), store.clone(), config.result_ttl));
+
+        Ok(Self {
+            jobs,
+            queue,
+            api_keys: config.api_keys,
+            metrics,
+            requests_per_minute: config.requests_per_minute,
+            max_jobs_per_client: config.max_jobs_per_client,
+            job_clients,
+            rate_windows: Mutex::default(),
+            store,
+            shutdown,
+            workers: Mutex::new(workers),
+        })
+    }
+
+    fn is_shutting_down(&self) -> bool {
+        *self.shutdown.borrow()
+    }
+
+    /// Stops the running jobs and waits until each has stored its best schedule so far.
+    /// Jobs still in the queue are left alone, so with a database they run after a restart.
+    pub async fn shutdown(&self) {
+        self.shutdown.send_replace(true);
+
+        for job in self.jobs.jobs.lock().unwrap().values() {
+            if matches!(job.snapshot().state, JobState::Running | JobState::Paused) {
+                job.stop_flag.store(true, Ordering::Relaxed);
+            }
+        }
+
+        let workers = std::mem::take(&mut *self.workers.lock().unwrap());
+        for worker in workers {
+            let _ = worker.await;
+        }
+    }
+
+    // Seconds to wait and why, when the client is over one of its limits
+    fn check_limits(&self, client: &str) -> Result<(), (u64, String)> {
+        if self.max_jobs_per_client > 0 {
+            let active = self.job_clients.lock().unwrap().values().filter(|c| *c == client).count();
+            if active >= self.max_jobs_per_client {
+                return Err((ACTIVE_JOBS_RETRY.as_secs(), format!("Masih ada {} job berjalan untuk klien ini", active)));
+            }
+        }
+
+        if self.requests_per_minute > 0 {
+            let now = Instant::now();
+            let mut windows = self.rate_windows.lock().unwrap();
+            windows.retain(|_, (start, _)| now.duration_since(*start) < RATE_WINDOW);
+
+            let (start, count) = windows.entry(client.to_string()).or_insert((now, 0));
+            if *count >= self.requests_per_minute {
+                let retry = RATE_WINDOW.saturating_sub(now.duration_since(*start)).as_secs().max(1);
+                return Err((retry, "Terlalu banyak permintaan optimasi, coba lagi nanti".to_string()));
+            }
+            *count += 1;
+        }
+
+        Ok(())
+    }
+}
+
+// Generated from the route annotations and the request/response types, served at /openapi.json
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Schedule Optimization API",
+        description = "Optimasi jadwal kuliah dengan PSO. Job dijalankan di belakang: POST /optimize mengembalikan job_id, progres dan hasil diambil lewat /jobs/{id}, /result/{id} atau WebSocket /ws."
+    ),
+    paths(optimize, stop, status, job, result, validate, ws, shutdown, metrics),
+    components(schemas(JobCommand)),
+    modifiers(&ApiKeyAuth),
+    security(("apiKey" = []))
+)]
+struct ApiDoc;
+
+struct ApiKeyAuth;
+
+impl Modify for ApiKeyAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let scheme = HttpBuilder::new()
+            .scheme(HttpAuthScheme::Bearer)
+            .description(Some("Hanya dibutuhkan bila server dijalankan dengan API key"))
+            .build();
+        openapi.components.get_or_insert_with(Default::default).add_security_scheme("apiKey", SecurityScheme::Http(scheme));
+    }
+}
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+// Suggested wait when a client already has its maximum of jobs, roughly a short run
+const ACTIVE_JOBS_RETRY: Duration = Duration::from_secs(30);
+
+// How often finished jobs past their TTL are dropped
+const EXPIRE_INTERVAL: Duration = Duration::from_secs(60);
+
+// How often /ws looks at the job status for something new to send
+const WS_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+impl IntoResponse for ScheduleError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            ScheduleError::ParseError { .. } | ScheduleError::InvalidParams { .. } => StatusCode::BAD_REQUEST,
+            ScheduleError::Stopped => StatusCode::CONFLICT,
+            ScheduleError::Busy { .. } => StatusCode::SERVICE_UNAVAILABLE,
+            ScheduleError::Internal { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(self)).into_response()
+    }
+}
+
+pub fn router(state: Arc<ServerState>) -> Router {
+    Router::new()
+        .route("/optimize", post(optimize).layer(middleware::from_fn_with_state(state.clone(), limit_clients)))
+        .route("/stop", post(stop))
+        .route("/status", get(status))
+        .route("/jobs/:id", get(job))
+        .route("/result/:id", get(result))
+        .route("/metrics", get(metrics))
+        .route("/validate", post(validate))
+        .route("/ws", get(ws))
+        .route("/admin/shutdown", post(shutdown))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_api_key))
+        // Added after the key check, so the docs stay readable without a key
+        // Swagger UI's files are built into the binary, so /docs also works without internet access
+        .merge(SwaggerUi::new("/docs").url("/openapi.json", ApiDoc::openapi()))
+        .with_state(state)
+}
+
+pub async fn serve(addr: SocketAddr, config: ServerConfig) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    println!("🌐 Server berjalan di http://{} ({} worker)", addr, config.workers);
+    if config.api_keys.is_empty() {
+        println!("⚠️  Tanpa API key, semua klien bisa menjalankan optimasi");
+    }
+    if let Some(database) = &config.database {
+        println!("💾 Job disimpan di {}", database.display());
+    }
+    let state = Arc::new(ServerState::new(config)?);
+    axum::serve(listener, router(state.clone()).into_make_service_with_connect_info::<SocketAddr>())
+        .with_graceful_shutdown(shutdown_signal(state))
+        .await
+}
+
+// Ctrl+C, SIGTERM or POST /admin/shutdown. Requests keep being answered until the running
+// jobs are stored, so clients can still fetch what they had.
+async fn shutdown_signal(state: Arc<ServerState>) {
+    let mut requested = state.shutdown.subscribe();
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate_signal() => {}
+        _ = requested.wait_for(|stopping| *stopping) => {}
+    }
+
+    println!("🛑 Server dimatikan, menunggu job yang berjalan menyimpan hasilnya...");
+    state.shutdown().await;
+    println!("✅ Semua job tersimpan");
+}
+
+#[cfg(unix)]
+async fn terminate_signal() {
+    match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+        Ok(mut signal) => {
+            signal.recv().await;
+        }
+        Err(_) => std::future::pending().await,
+    }
+}
+
+#[cfg(not(unix))]
+async fn terminate_signal() {
+    std::future::pending().await
+}
+
+// Accepts `Authorization: Bearer <key>`, or `?api_key=<key>` on /ws for WebSocket clients
+// that cannot set headers
+async fn require_api_key(
+    State(state): State<Arc<ServerState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    if state.api_keys.is_empty() {
+        return Ok(next.run(request).await);
+    }
+
+    // Every configured key is compared in full, so the response time does not tell how much of a key matched
+    let valid = api_key(&request)
+        .is_some_and(|key| state.api_keys.iter().fold(false, |found, k| found | constant_time_eq(k.as_bytes(), key.as_bytes())));
+    if valid {
+        Ok(next.run(request).await)
+    } else {
+        Err(StatusCode::UNAUTHORIZED)
+    }
+}
+
+fn api_key(request: &Request) -> Option<&str> {
+    let bearer = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let query = (request.uri().path() == "/ws")
+        .then(|| request.uri().query())
+        .flatten()
+        .and_then(|query| query.split('&').find_map(|pair| pair.strip_prefix("api_key=")));
+
+    bearer.or(query)
+}
+
+// Equal when both have the same length and bytes, looking at every byte either way
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+// Per-client request rate and job count on /optimize, answered with 429 and Retry-After
+async fn limit_clients(State(state): State<Arc<ServerState>>, mut request: Request, next: Next) -> Response {
+    let client = match api_key(&request) {
+        Some(key) if !state.api_keys.is_empty() => format!("key:{}", key),
+        _ => request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string())
+            .unwrap_or_default(),
+    };
+
+    if let Err((retry_after, message)) = state.check_limits(&client) {
+        let error = ScheduleError::Busy { message };
+        return (StatusCode::TOO_MANY_REQUESTS, [(RETRY_AFTER, retry_after.to_string())], Json(error)).into_response();
+    }
+
+    request.extensions_mut().insert(Client(client));
+    next.run(request).await
+}
+
+// Queues a job and returns its id; the result shows up in /jobs/{id} once it is done
+#[utoipa::path(
+    post,
+    path = "/optimize",
+    summary = "Antrikan job optimasi",
+    request_body = OptimizeRequest,
+    responses(
+        (status = 200, description = "Job masuk antrian", body = JobCreated),
+        (status = 400, description = "Input tidak valid", body = ScheduleError),
+        (status = 401, description = "API key tidak ada atau salah"),
+        (
+            status = 429,
+            description = "Klien melewati batas permintaan per menit atau jumlah job aktif",
+            body = ScheduleError,
+            headers(("Retry-After" = u64, description = "Detik sebelum mencoba lagi"))
+        ),
+        (status = 503, description = "Antrian penuh atau server sedang dimatikan", body = ScheduleError),
+    )
+)]
+async fn optimize(
+    State(state): State<Arc<ServerState>>,
+    Extension(Client(client)): Extension<Client>,
+    Json(request): Json<OptimizeRequest>,
+) -> Result<Json<JobCreated>, ScheduleError> {
+    if state.is_shutting_down() {
+        return Err(ScheduleError::Busy { message: "Server sedang dimatikan".to_string() });
+    }
+
+    // Kept as sent, so a restarted server can parse it again
+    let request_json = state.store.as_ref().map(|_| serde_json::to_string(&request).unwrap_or_default());
+
+    let job = state.jobs.start_job();
+    let queued = match queued_job(job.clone(), request) {
+        Ok(queued) => queued,
+        Err(e) => {
+            state.jobs.jobs.lock().unwrap().remove(&job.id);
+            return Err(e);
+        }
+    };
+    job.status.lock().unwrap().state = JobState::Queued;
+
+    state.job_clients.lock().unwrap().insert(job.id, client.clone());
+    if let (Some(store), Some(request_json)) = (&state.store, &request_json) {
+        if let Err(e) = store.insert(job.id, &client, request_json) {
+            eprintln!("⚠️  Gagal menyimpan job {}: {}", job.id, e);
+        }
+    }
+
+    if state.queue.try_send(queued).is_err() {
+        state.jobs.jobs.lock().unwrap().remove(&job.id);
+        state.job_clients.lock().unwrap().remove(&job.id);
+        if let Some(store) = &state.store {
+            let _ = store.remove(job.id);
+        }
+        return Err(ScheduleError::Busy { message: "Antrian optimasi penuh, coba lagi nanti".to_string() });
+    }
+
+    Ok(Json(JobCreated { job_id: job.id }))
+}
+
+fn queued_job(job: Arc<Job>, request: OptimizeRequest) -> Result<QueuedJob, ScheduleError> {
+    let params = request.params;
+    if params.swarm_size <= 0 || params.max_iterations == 0 {
+        return Err(ScheduleError::invalid("swarm_size dan max_iterations harus lebih dari 0"));
+    }
+
+    let courses = parse_course_csv(&request.course_csv)?;
+    let time_preferences = load_preferences(&request.preference_csv, request.lecturer_csv, request.absence_csv)?;
+
+    Ok(QueuedJob { job, courses, time_preferences, params })
+}
+
+// Finished jobs come back with their results; unfinished ones are queued again from the
+// start, since the server keeps no swarm checkpoints
+fn restore_jobs(
+    store: &JobStore,
+    jobs: &AppState,
+    job_clients: &Mutex<HashMap<JobId, String>>,
+    queue: mpsc::Sender<QueuedJob>,
+) -> rusqlite::Result<()> {
+    jobs.next_job_id.store(store.last_id()?, Ordering::Relaxed);
+
+    let restored_job = |status: JobStatus| {
+        let job = Arc::new(Job {
+            id: status.job_id,
+            stop_flag: Arc::new(AtomicBool::new(false)),
+            pause: Arc::new(PauseControl::default()),
+            status: Arc::new(Mutex::new(status)),
+        });
+        jobs.jobs.lock().unwrap().insert(job.id, job.clone());
+        job
+    };
+
+    for status in store.finished()? {
+        restored_job(status);
+    }
+
+    let mut requeued = Vec::new();
+    for pending in store.pending()? {
+        let job = restored_job(JobStatus {
+            job_id: pending.job_id,
+            state: JobState::Queued,
+            iteration: 0,
+            max_iterations: 0,
+            current_run: 0,
+            total_runs: 0,
+            best_fitness: f64::INFINITY,
+            result: None,
+            checkpoint_id: None,
+            started_at: chrono::Local::now(),
+            finished_at: None,
+        });
+
+        let request = serde_json::from_str::<OptimizeRequest>(&pending.request)
+            .map_err(|e| ScheduleError::from(format!("Job tersimpan rusak: {}", e)));
+        match request.and_then(|request| queued_job(job.clone(), request)) {
+            Ok(queued) => {
+                job_clients.lock().unwrap().insert(job.id, pending.client);
+                requeued.push(queued);
+            }
+            Err(e) => {
+                job.finish(finished_event(job.id, Err(e)));
+                store.finish(&job.snapshot())?;
+            }
+        }
+    }
+
+    if !requeued.is_empty() {
+        println!("🔁 {} job dilanjutkan dari database", requeued.len());
+    }
+    // More may be waiting than the queue holds, so they are handed over as workers free up
+    tokio::spawn(async move {
+        for queued in requeued {
+            if queue.send(queued).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    Ok(())
+}
+
+async fn process(queued: QueuedJob, metrics: &Metrics, store: Option<&JobStore>) {
+    let QueuedJob { job, courses, time_preferences, params } = queued;
+
+    // Stopped while still waiting in the queue
+    let result = if job.stop_flag.load(Ordering::Relaxed) {
+        Err(ScheduleError::Stopped)
+    } else {
+        {
+            let mut status = job.status.lock().unwrap();
+            status.state = JobState::Running;
+            status.started_at = chrono::Local::now();
+        }
+        if let Some(store) = store {
+            if let Err(e) = store.set_state(job.id, JobState::Running) {
+                eprintln!("⚠️  Gagal menyimpan job {}: {}", job.id, e);
+            }
+        }
+        // A run is CPU-bound and blocks while paused, so it gets a blocking thread and the
+        // async workers stay free for /status, /stop and /ws
+        let (running, runtime) = (job.clone(), tokio::runtime::Handle::current());
+        tokio::task::spawn_blocking(move || runtime.block_on(run(&running, courses, time_preferences, params)))
+            .await
+            .unwrap_or_else(|e| Err(ScheduleError::from(format!("Optimasi gagal: {}", e))))
+    };
+
+    let counter = match &result {
+        Err(ScheduleError::Stopped) => &metrics.stopped_total,
+        Err(_) => &metrics.failed_total,
+        Ok(_) if job.stop_flag.load(Ordering::Relaxed) => &metrics.stopped_total,
+        Ok(_) => &metrics.finished_total,
+    };
+    counter.fetch_add(1, Ordering::Relaxed);
+
+    job.finish(finished_event(job.id, result));
+    let status = job.snapshot();
+    metrics.iterations_done.fetch_add(status.iterations_done() as u64, Ordering::Relaxed);
+
+    if let Some(store) = store {
+        if let Err(e) = store.finish(&status) {
+            eprintln!("⚠️  Gagal menyimpan hasil job {}: {}", job.id, e);
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/stop",
+    summary = "Hentikan job; hasil terbaik sejauh ini tetap disimpan",
+    request_body = JobQuery,
+    responses(
+        (status = 204, description = "Permintaan berhenti diterima"),
+        (status = 400, description = "Job tidak ditemukan", body = ScheduleError),
+        (status = 401, description = "API key tidak ada atau salah"),
+    )
+)]
+async fn stop(State(state): State<Arc<ServerState>>, Json(query): Json<JobQuery>) -> Result<StatusCode, ScheduleError> {
+    state.jobs.job(query.job_id)?.stop_flag.store(true, Ordering::Relaxed);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Starts the same shutdown as a signal would; answers right away, the server exits once
+// the running jobs are stored
+#[utoipa::path(
+    post,
+    path = "/admin/shutdown",
+    summary = "Matikan server dengan aman",
+    description = "Job yang berjalan dihentikan dan hasil terbaiknya disimpan sebelum server keluar. Job yang masih mengantri tetap di database dan dijalankan lagi saat server hidup kembali.",
+    responses(
+        (status = 202, description = "Server mulai dimatikan"),
+        (status = 401, description = "API key tidak ada atau salah"),
+    )
+)]
+async fn shutdown(State(state): State<Arc<ServerState>>) -> StatusCode {
+    state.shutdown.send_replace(true);
+    StatusCode::ACCEPTED
+}
+
+#[utoipa::path(
+    get,
+    path = "/status",
+    summary = "Status job (sama dengan /jobs/{id})",
+    params(JobQuery),
+    responses(
+        (status = 200, description = "Status job", body = JobStatus),
+        (status = 400, description = "Job tidak ditemukan", body = ScheduleError),
+        (status = 401, description = "API key tidak ada atau salah"),
+    )
+)]
+async fn status(State(state): State<Arc<ServerState>>, Query(query): Query<JobQuery>) -> Result<Json<JobStatus>, ScheduleError> {
+    Ok(Json(state.jobs.job(query.job_id)?.snapshot()))
+}
+
+// Status of a job, with its result once finished
+#[utoipa::path(
+    get,
+    path = "/jobs/{id}",
+    summary = "Status job, beserta hasilnya setelah selesai",
+    params(("id" = u64, Path, description = "job_id dari /optimize")),
+    responses(
+        (status = 200, description = "Status job", body = JobStatus),
+        (status = 400, description = "Job tidak ditemukan", body = ScheduleError),
+        (status = 401, description = "API key tidak ada atau salah"),
+    )
+)]
+async fn job(State(state): State<Arc<ServerState>>, Path(job_id): Path<JobId>) -> Result<Json<JobStatus>, ScheduleError> {
+    Ok(Json(state.jobs.job(job_id)?.snapshot()))
+}
+
+// Result of a finished job; 202 with the current status while it is still queued or running
+#[utoipa::path(
+    get,
+    path = "/result/{id}",
+    summary = "Hasil job yang sudah selesai",
+    params(("id" = u64, Path, description = "job_id dari /optimize")),
+    responses(
+        (status = 200, description = "Hasil optimasi; job yang gagal berisi success = false, message dan error", body = OptimizeResult),
+        (status = 202, description = "Job masih mengantri atau berjalan", body = JobStatus),
+        (status = 400, description = "Job tidak ditemukan", body = ScheduleError),
+        (status = 401, description = "API key tidak ada atau salah"),
+    )
+)]
+async fn result(State(state): State<Arc<ServerState>>, Path(job_id): Path<JobId>) -> Result<Response, ScheduleError> {
+    let status = state.jobs.job(job_id)?.snapshot();
+    Ok(match status.result {
+        Some(result) => Json(result).into_response(),
+        None => (StatusCode::ACCEPTED, Json(status)).into_response(),
+    })
+}
+
+// Prometheus text format, written by hand since it is only a few lines
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    summary = "Metrik Prometheus",
+    responses(
+        (status = 200, description = "Format teks Prometheus", body = String, content_type = "text/plain"),
+        (status = 401, description = "API key tidak ada atau salah"),
+    )
+)]
+async fn metrics(State(state): State<Arc<ServerState>>) -> String {
+    let now = chrono::Local::now();
+    let statuses: Vec<JobStatus> = state.jobs.jobs.lock().unwrap().values().map(|job| job.snapshot()).collect();
+    let active: Vec<&JobStatus> =
+        statuses.iter().filter(|s| matches!(s.state, JobState::Running | JobState::Paused)).collect();
+    let count = |state: JobState| statuses.iter().filter(|s| s.state == state).count();
+    let metrics = &state.metrics;
+
+    let mut out = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, samples: &[(String, f64)]| {
+        let _ = writeln!(out, "# HELP {} {}\n# TYPE {} {}", name, help, name, kind);
+        for (labels, value) in samples {
+            let _ = writeln!(out, "{}{} {}", name, labels, value);
+        }
+    };
+
+    metric("schedule_jobs", "gauge", "Jobs currently known to the server by state", &[
+        ("{state=\"queued\"}".to_string(), count(JobState::Queued) as f64),
+        ("{state=\"running\"}".to_string(), count(JobState::Running) as f64),
+        ("{state=\"paused\"}".to_string(), count(JobState::Paused) as f64),
+    ]);
+    metric("schedule_optimizations_total", "counter", "Optimization jobs that ended, by outcome", &[
+        ("{outcome=\"finished\"}".to_string(), metrics.finished_total.load(Ordering::Relaxed) as f64),
+        ("{outcome=\"stopped\"}".to_string(), metrics.stopped_total.load(Ordering::Relaxed) as f64),
+        ("{outcome=\"failed\"}".to_string(), metrics.failed_total.load(Ordering::Relaxed) as f64),
+    ]);
+
+    let running_iterations: usize = active.iter().map(|s| s.iterations_done()).sum();
+    metric("schedule_pso_iterations_total", "counter", "PSO iterations done by all jobs", &[(
+        String::new(),
+        (metrics.iterations_done.load(Ordering::Relaxed) + running_iterations as u64) as f64,
+    )]);
+
+    let per_job = |value: &dyn Fn(&JobStatus) -> Option<f64>| -> Vec<(String, f64)> {
+        active.iter().filter_map(|s| Some((format!("{{job_id=\"{}\"}}", s.job_id), value(s)?))).collect()
+    };
+    metric("schedule_job_iterations_per_second", "gauge", "Iteration rate of each active job since it started", &per_job(&|s| {
+        let elapsed = (now - s.started_at).to_std().ok()?.as_secs_f64();
+        (elapsed > 0.0).then(|| s.iterations_done() as f64 / elapsed)
+    }));
+    metric("schedule_job_best_fitness", "gauge", "Best fitness of each active job so far", &per_job(&|s| {
+        s.best_fitness.is_finite().then_some(s.best_fitness)
+    }));
+
+    out
+}
+
+async fn expire_results(jobs: Arc<AppState>, store: Option<Arc<JobStore>>, ttl: Duration) {
+    let mut ticker = tokio::time::interval(EXPIRE_INTERVAL);
+    loop {
+        ticker.tick().await;
+        let now = chrono::Local::now();
+        jobs.jobs.lock().unwrap().retain(|_, job| {
+            let finished_at = job.status.lock().unwrap().finished_at;
+            finished_at.is_none_or(|at| (now - at).to_std().unwrap_or_default() < ttl)
+        });
+
+        if let (Some(store), Ok(ttl)) = (&store, chrono::Duration::from_std(ttl)) {
+            if let Err(e) = store.cleanup(now - ttl) {
+                eprintln!("⚠️  Gagal membersihkan job lama: {}", e);
+            }
+        }
+    }
+}
+
+// Progress of a job pushed as JobStatus messages, with stop/pause/resume coming back on the
+// same connection. The last message carries the result, after which the socket is closed.
+#[utoipa::path(
+    get,
+    path = "/ws",
+    summary = "WebSocket progres job",
+    description = "Server mengirim JobStatus setiap ada perubahan; pesan terakhir membawa hasil lalu koneksi ditutup. Klien boleh mengirim JobCommand. Klien yang tidak bisa mengirim header memakai query api_key.",
+    params(JobQuery, ("api_key" = Option<String>, Query, description = "Pengganti header Authorization")),
+    responses(
+        (status = 101, description = "Upgrade ke WebSocket"),
+        (status = 400, description = "Job tidak ditemukan", body = ScheduleError),
+        (status = 401, description = "API key tidak ada atau salah"),
+    )
+)]
+async fn ws(
+    upgrade: WebSocketUpgrade,
+    State(state): State<Arc<ServerState>>,
+    Query(query): Query<JobQuery>,
+) -> Result<Response, ScheduleError> {
+    let job = state.jobs.job(query.job_id)?;
+    Ok(upgrade.on_upgrade(move |socket| job_socket(socket, job)))
+}
+
+async fn job_socket(mut socket: WebSocket, job: Arc<Job>) {
+    let mut ticker = tokio::time::interval(WS_POLL_INTERVAL);
+    let mut last_sent = None;
+    let mut paused_here = false;
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                let status = job.snapshot();
+                let done = matches!(status.state, JobState::Finished | JobState::Stopped);
+                let progress = (status.current_run, status.iteration, status.state);
+                if last_sent != Some(progress) || done {
+                    last_sent = Some(progress);
+                    if send_json(&mut socket, json!(status)).await.is_err() || done {
+                        break;
+                    }
+                }
+            }
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => match serde_json::from_str::<JobCommand>(&text) {
+                    Ok(JobCommand::Stop) => job.stop_flag.store(true, Ordering::Relaxed),
+                    Ok(JobCommand::Pause) => {
+                        job.pause.pause();
+                        paused_here = true;
+                    }
+                    Ok(JobCommand::Resume) => {
+                        job.pause.resume();
+                        paused_here = false;
+                    }
+                    Err(e) => {
+                        let error = ScheduleError::invalid(format!("Perintah tidak dikenal: {}", e));
+                        if send_json(&mut socket, json!(error)).await.is_err() {
+                            break;
+                        }
+                    }
+                },
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            }
+        }
+    }
+
+    // A client that goes away must not leave its pause holding the job forever
+    if paused_here {
+        job.pause.resume();
+    }
+}
+
+async fn send_json(socket: &mut WebSocket, value: Value) -> Result<(), axum::Error> {
+    socket.send(Message::Text(value.to_string())).await
+}
+
+// Same report as the evaluate_schedule command, computed right away
+#[utoipa::path(
+    post,
+    path = "/validate",
+    summary = "Periksa konflik dan preferensi pada jadwal yang diedit manual",
+    request_body = ValidateRequest,
+    responses(
+        (status = 200, description = "Hasil pemeriksaan", body = ValidateResult),
+        (status = 400, description = "Input tidak valid", body = ScheduleError),
+        (status = 401, description = "API key tidak ada atau salah"),
+    )
+)]
+async fn validate(Json(request): Json<ValidateRequest>) -> Result<Json<ValidateResult>, ScheduleError> {
+    let time_preferences = load_preferences(&request.preference_csv, request.lecturer_csv, request.absence_csv)?;
+    let checker = ScheduleChecker::new(time_preferences, request.constraints, request.penalty_weights);
+    let schedule = request.schedule;
+    let evaluation = checker.evaluate(&schedule);
+
+    Ok(Json(ValidateResult {
+        fitness: evaluation.fitness(),
+        is_feasible: evaluation.is_feasible(),
+        hard_violations: evaluation.hard_violations,
+        soft_penalty: evaluation.soft_penalty,
+        conflicts: checker.detect_conflicts(&schedule),
+        preferences: checker.check_preferences(&schedule),
+        message: checker.evaluate_messages(&schedule),
+    }))
+}
+
+// The process_pso loop without events, history or checkpoints
+async fn run(
+    job: &Job,
+    courses: Vec<CourseRequest>,
+    time_preferences: Vec<LecturerPreference>,
+    params: PsoParameters,
+) -> Result<Value, ScheduleError> {
+    let (schedule, fitness) = PSO::optimize_best(courses, time_preferences.clone(), &params, job.stop_flag.clone(), |pso| {
+        pso.job_id = Some(job.id);
+        pso.status = Some(job.status.clone());
+        pso.pause = Some(job.pause.clone());
+    })
+    .await?;
+
+    let checker = ScheduleChecker::new(time_preferences, params.constraints.clone(), params.penalty_weights.clone());
+    let evaluation = checker.evaluate(&schedule);
+
+    let result = OptimizeResult {
+        job_id: job.id,
+        success: true,
+        fitness,
+        is_feasible: evaluation.is_feasible(),
+        hard_violations: evaluation.hard_violations,
+        soft_penalty: evaluation.soft_penalty,
+        message: checker.evaluate_messages(&schedule),
+        stopped_early: job.stop_flag.load(Ordering::Relaxed),
+        schedule,
+    };
+    serde_json::to_value(result).map_err(|e| ScheduleError::from(e.to_string()))
+}
@@ -0,0 +1,89 @@
+use std::fs;
+use std::path::PathBuf;
+
+use super::models::{RunRecord, RunSummary};
+
+/// Past optimization runs, one JSON file per run so a broken file only loses that run
+pub struct RunHistory {
+    dir: PathBuf,
+}
+
+impl RunHistory {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn save(&self, record: &RunRecord) -> Result<(), String> {
+        fs::create_dir_all(&self.dir).map_err(|e| format!("Gagal membuat folder riwayat: {}", e))?;
+        let json = serde_json::to_string(record).map_err(|e| e.to_string())?;
+        fs::write(self.path(&record.id)?, json).map_err(|e| format!("Gagal menyimpan riwayat: {}", e))
+    }
+
+    /// Newest first; files that no longer parse are skipped
+    pub fn list(&self) -> Result<Vec<RunSummary>, String> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("Gagal membaca folder riwayat: {}", e)),
+        };
+
+        let mut runs: Vec<RunSummary> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+            .filter_map(|json| serde_json::from_str::<RunRecord>(&json).ok())
+            .map(|run| RunSummary {
+                id: run.id,
+                dibuat: run.dibuat,
+                input_hash: run.input_hash,
+                fitness: run.fitness,
+                durasi: run.durasi,
+                jumlah_jadwal: run.schedule.len(),
+            })
+            .collect();
+        runs.sort_by_key(|run| std::cmp::Reverse(run.dibuat));
+
+        Ok(runs)
+    }
+
+    pub fn get(&self, id: &str) -> Result<RunRecord, String> {
+        let json = fs::read_to_string(self.path(id)?)
+            .map_err(|_| format!("Riwayat run {} tidak ditemukan", id))?;
+        serde_json::from_str(&json).map_err(|e| format!("Riwayat run {} rusak: {}", id, e))
+    }
+
+    // Ids come from the frontend, so nothing that could leave the folder
+    fn path(&self, id: &str) -> Result<PathBuf, String> {
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(format!("Id run tidak valid: {}", id));
+        }
+        Ok(self.dir.join(format!("{}.json", id)))
+    }
+}
+
+/// Same inputs give the same hash, so runs on identical data can be grouped. FNV-1a rather
+/// than DefaultHasher, whose output may change between Rust releases while the hashes are stored.
+pub fn input_hash(inputs: &[Option<&str>]) -> String {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = FNV_OFFSET;
+    let mut feed = |bytes: &[u8]| {
+        for &byte in bytes {
+            hash = (hash ^ byte as u64).wrapping_mul(FNV_PRIME);
+        }
+    };
+
+    // Tag and length first, so a missing input, an empty one and shifted boundaries all differ
+    for input in inputs {
+        match input {
+            None => feed(&[0]),
+            Some(text) => {
+                feed(&[1]);
+                feed(&(text.len() as u64).to_le_bytes());
+                feed(text.as_bytes());
+            }
+        }
+    }
+    format!("{:016x}", hash)
+}
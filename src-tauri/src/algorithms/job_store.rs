@@ -0,0 +1,119 @@
+use std::path::Path;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Local};
+use rusqlite::{params, Connection};
+
+use super::models::{JobId, JobState, JobStatus};
+
+/// HTTP jobs in SQLite, so a restarted server still has its queue and finished results.
+/// Requests are kept as the JSON they came in as and re-parsed when a job is picked up again.
+pub struct JobStore {
+    conn: Mutex<Connection>,
+}
+
+/// A job that had not finished when the server went down
+pub struct PendingJob {
+    pub job_id: JobId,
+    pub client: String,
+    pub request: String,
+}
+
+impl JobStore {
+    pub fn open(path: &Path) -> rusqlite::Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS jobs (
+                id          INTEGER PRIMARY KEY,
+                client      TEXT NOT NULL,
+                state       TEXT NOT NULL,
+                request     TEXT NOT NULL,
+                status      TEXT,
+                created_at  TEXT NOT NULL,
+                finished_at TEXT
+            );
+            CREATE INDEX IF NOT EXISTS jobs_finished_at ON jobs (finished_at);
+            -- Highest id handed out; unlike MAX(jobs.id) it survives cleanup, so ids are never reused
+            CREATE TABLE IF NOT EXISTS job_sequence (last_id INTEGER NOT NULL);
+            INSERT INTO job_sequence (last_id)
+                SELECT (SELECT COALESCE(MAX(id), 0) FROM jobs) WHERE NOT EXISTS (SELECT 1 FROM job_sequence);",
+        )?;
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+
+    pub fn insert(&self, job_id: JobId, client: &str, request: &str) -> rusqlite::Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT INTO jobs (id, client, state, request, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![job_id as i64, client, state_name(JobState::Queued), request, Local::now().to_rfc3339()],
+        )?;
+        tx.execute("UPDATE job_sequence SET last_id = MAX(last_id, ?1)", params![job_id as i64])?;
+        tx.commit()
+    }
+
+    pub fn set_state(&self, job_id: JobId, state: JobState) -> rusqlite::Result<()> {
+        self.conn
+            .lock()
+            .unwrap()
+            .execute("UPDATE jobs SET state = ?1 WHERE id = ?2", params![state_name(state), job_id as i64])?;
+        Ok(())
+    }
+
+    /// Stores the final status, result included
+    pub fn finish(&self, status: &JobStatus) -> rusqlite::Result<()> {
+        let json = serde_json::to_string(status).unwrap_or_default();
+        let finished_at = status.finished_at.unwrap_or_else(Local::now).to_rfc3339();
+        self.conn.lock().unwrap().execute(
+            "UPDATE jobs SET state = ?1, status = ?2, finished_at = ?3 WHERE id = ?4",
+            params![state_name(status.state), json, finished_at, status.job_id as i64],
+        )?;
+        Ok(())
+    }
+
+    pub fn remove(&self, job_id: JobId) -> rusqlite::Result<()> {
+        self.conn.lock().unwrap().execute("DELETE FROM jobs WHERE id = ?1", params![job_id as i64])?;
+        Ok(())
+    }
+
+    /// Queued or running jobs, oldest first
+    pub fn pending(&self) -> rusqlite::Result<Vec<PendingJob>> {
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn.prepare("SELECT id, client, request FROM jobs WHERE finished_at IS NULL ORDER BY id")?;
+        let rows = statement.query_map([], |row| {
+            Ok(PendingJob { job_id: row.get::<_, i64>(0)? as JobId, client: row.get(1)?, request: row.get(2)? })
+        })?;
+        rows.collect()
+    }
+
+    /// Final status of finished jobs; rows whose status no longer parses are skipped
+    pub fn finished(&self) -> rusqlite::Result<Vec<JobStatus>> {
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn.prepare("SELECT status FROM jobs WHERE finished_at IS NOT NULL ORDER BY id")?;
+        let rows = statement.query_map([], |row| row.get::<_, Option<String>>(0))?;
+
+        Ok(rows
+            .filter_map(|row| row.ok().flatten())
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect())
+    }
+
+    /// Highest id ever handed out, including jobs already cleaned up, so new jobs do not reuse one
+    pub fn last_id(&self) -> rusqlite::Result<JobId> {
+        let conn = self.conn.lock().unwrap();
+        let id: i64 = conn.query_row("SELECT last_id FROM job_sequence", [], |row| row.get(0))?;
+        Ok(id as JobId)
+    }
+
+    /// Drops jobs that finished before `before`
+    pub fn cleanup(&self, before: DateTime<Local>) -> rusqlite::Result<usize> {
+        self.conn
+            .lock()
+            .unwrap()
+            .execute("DELETE FROM jobs WHERE finished_at IS NOT NULL AND finished_at < ?1", params![before.to_rfc3339()])
+    }
+}
+
+fn state_name(state: JobState) -> String {
+    serde_json::to_value(state).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default()
+}
@@ -1,4 +1,16 @@
 pub mod optimizer;
 pub mod tune;
 pub mod models;
-pub mod checker;
\ No newline at end of file
+pub mod checker;
+pub mod preference;
+pub mod validation;
+pub mod history;
+pub mod export;
+pub mod checkpoint;
+pub mod error;
+pub mod template;
+pub mod batch;
+#[cfg(feature = "server")]
+pub mod handlers;
+#[cfg(feature = "server")]
+pub mod job_store;
@@ -1,16 +1,40 @@
+use chrono::{DateTime, Local, NaiveDate};
+use lru::LruCache;
+use rand::{rngs::StdRng, SeedableRng};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, time::Duration};
+use std::{collections::{BTreeMap, HashMap}, sync::{Arc, Condvar, Mutex}, time::Duration};
 
-#[derive(Debug, Clone)]
+use super::checker::Constraint;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Particle {
     pub position: Vec<f64>,
     pub velocity: Vec<f64>,
     pub pbest_position: Vec<f64>,
     pub pbest_fitness: f64,
     pub fitness: f64,
+    pub evaluation: Evaluation,
+    pub pbest_evaluation: Evaluation,
+    #[serde(skip)]
+    pub cache: DecodeCache,
+    // A restored particle continues with a fresh, unseeded stream
+    #[serde(skip, default = "unseeded_rng")]
+    pub rng: StdRng,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+fn unseeded_rng() -> StdRng {
+    StdRng::from_rng(&mut rand::rng())
+}
+
+// Hasil evaluasi jadwal: pelanggaran hard constraint dan penalti soft constraint
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Evaluation {
+    pub hard_violations: u32,
+    pub hard_penalty: f64,
+    pub soft_penalty: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CourseRequest {
     #[serde(rename = "id")] 
     pub id_jadwal: u32,
@@ -21,6 +45,16 @@ pub struct CourseRequest {
     pub semester: u32,
     pub sks: u32,
     pub prodi: u32,
+    #[serde(default)]
+    pub fixed_hari: Option<u32>,       // Jadwal dikunci pada hari ini
+    #[serde(default)]
+    pub fixed_jam_mulai: Option<u32>,  // Jadwal dikunci pada jam mulai ini (menit)
+    #[serde(default)]
+    pub id_ruangan: Option<u32>,
+    #[serde(default)]
+    pub is_online: bool,               // Kelas daring, tidak membutuhkan ruangan
+    #[serde(default)]
+    pub pertemuan: u32,                // Pertemuan ke- bila dipecah, 0 = tidak dipecah
 }
 
 #[derive(Clone, Serialize)]
@@ -37,30 +71,118 @@ pub struct OptimizationProgress {
         pub current_run: Option<usize>,          // Menjadi opsional
         pub total_runs: Option<usize>,           // Menjadi opsional
         pub is_finished: bool,
+        pub hard_violations: u32,
+        pub soft_penalty: f64,
+        pub breakdown: BTreeMap<&'static str, f64>,  // Penalti global best per jenis constraint
+        pub cache_hit_rate: f64,
+        pub job_id: Option<JobId>,
         // pub conflicts: ConflictInfo,
 }
 
+// Isi file masukan: teks CSV, sheet pertama workbook .xlsx (path atau bytes) dengan kolom yang sama,
+// atau langsung array baris bertipe dari frontend. String biasa tetap dibaca sebagai CSV
 #[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum InputSource<T> {
+    Csv(String),
+    XlsxPath { xlsx_path: String },
+    Xlsx { xlsx: Vec<u8> },
+    Rows(Vec<T>),
+}
+
+// Nilai kolom boleh boolean lama (true = prefer, false = avoid) atau prefer/neutral/avoid
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TimePreferenceRequest {
     pub id_dosen: u32,
-    pub senin_pagi: bool,
-    pub senin_malam: bool,
-    pub selasa_pagi: bool,
-    pub selasa_malam: bool,
-    pub rabu_pagi: bool,
-    pub rabu_malam: bool,
-    pub kamis_pagi: bool,
-    pub kamis_malam: bool,
-    pub jumat_pagi: bool,
-    pub jumat_malam: bool,
+    pub senin_pagi: PreferenceLevel,
+    pub senin_malam: PreferenceLevel,
+    pub selasa_pagi: PreferenceLevel,
+    pub selasa_malam: PreferenceLevel,
+    pub rabu_pagi: PreferenceLevel,
+    pub rabu_malam: PreferenceLevel,
+    pub kamis_pagi: PreferenceLevel,
+    pub kamis_malam: PreferenceLevel,
+    pub jumat_pagi: PreferenceLevel,
+    pub jumat_malam: PreferenceLevel,
+    #[serde(default)]
+    pub sabtu_pagi: Option<PreferenceLevel>,   // Kolom opsional, kosong berarti neutral
+    #[serde(default)]
+    pub sabtu_malam: Option<PreferenceLevel>,
+    #[serde(default)]
+    pub minggu_pagi: Option<PreferenceLevel>,
+    #[serde(default)]
+    pub minggu_malam: Option<PreferenceLevel>,
+    #[serde(default)]
+    pub back_to_back: Option<BackToBack>,  // Kolom opsional: require / forbid
+}
+
+pub const SLOT_MINUTES: u32 = 30;
+pub const SLOTS_PER_DAY: u32 = 24 * 60 / SLOT_MINUTES;
+pub const EVENING_START: u32 = 1080;  // Batas pagi/malam pada format preferensi lama
+
+// Format preferensi baru: satu baris per interval ketersediaan dosen (menit)
+#[derive(Debug, Deserialize, Clone)]
+pub struct AvailabilityRequest {
+    pub id_dosen: u32,
+    pub hari: u32,
+    pub jam_mulai: u32,
+    pub jam_akhir: u32,
+    #[serde(default)]
+    pub tingkat: Option<PreferenceLevel>,  // Default prefer
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum PreferenceLevel {
+    Prefer,
+    Neutral,
+    Avoid,
 }
 
 #[derive(Debug, Clone)]
-pub struct FitnessCalculator {
-    pub time_preferences: HashMap<u32, TimePreferenceRequest>,
+pub struct LecturerPreference {
+    pub id_dosen: u32,
+    pub prefer: [u64; 7],  // Bitmap slot 30 menit per hari, indeks hari - 1
+    pub avoid: [u64; 7],   // Slot yang tidak di keduanya dianggap neutral
+    pub back_to_back: Option<BackToBack>,
+    pub max_kelas_per_minggu: Option<u32>,
+    pub prioritas: u32,  // Pengali penalti preferensi, 1 = normal
+    pub max_malam_per_minggu: Option<u32>,  // Batas jumlah malam mengajar per minggu
+    pub bobot_hari_mengajar: Option<u32>,   // Override bobot_hari_dosen untuk dosen ini
+    pub absen: Vec<(NaiveDate, NaiveDate)>,  // Rentang tanggal dosen berhalangan (inklusif)
 }
 
-#[derive(Debug, Serialize, Clone)]
+// CSV ketidakhadiran dosen pada tanggal tertentu (konferensi, cuti, dsb.)
+#[derive(Debug, Deserialize, Clone)]
+pub struct AbsenceRequest {
+    pub id_dosen: u32,
+    pub tanggal_mulai: NaiveDate,  // YYYY-MM-DD
+    pub tanggal_akhir: NaiveDate,
+}
+
+// Metadata dosen dari CSV terpisah, digabung ke LecturerPreference berdasarkan id_dosen
+#[derive(Debug, Deserialize, Clone)]
+pub struct LecturerRequest {
+    pub id_dosen: u32,
+    #[serde(default)]
+    pub max_kelas_per_minggu: Option<u32>,
+    #[serde(default)]
+    pub prioritas: Option<u32>,  // Mis. 2 untuk dosen senior/luar biasa
+    #[serde(default)]
+    pub max_malam_per_minggu: Option<u32>,
+    #[serde(default)]
+    pub bobot_hari_mengajar: Option<u32>,  // Mis. tinggi untuk dosen paruh waktu
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BackToBack {
+    Require,  // Sesi dalam satu hari harus berurutan tanpa jeda
+    Forbid,   // Sesi tidak boleh langsung berurutan
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 pub struct OptimizedCourse {
     pub id_jadwal: u32,
     pub id_matkul: u32,
@@ -74,30 +196,541 @@ pub struct OptimizedCourse {
     pub semester: u32,
     pub sks: u32,
     pub prodi: u32,
+    #[serde(default)]
+    pub is_online: bool,
+    #[serde(default)]
+    pub pertemuan: u32,
 }
 
 pub struct PSO {
     pub particles: Vec<Particle>,
     pub global_best_position: Vec<f64>,
     pub global_best_fitness: f64,
+    pub global_best_evaluation: Evaluation,
     pub parameters: PsoParameters,
     pub courses: Vec<CourseRequest>,
+    pub groups: Vec<Vec<usize>>,  // Indeks course per kelompok kelas
     pub checker: ScheduleChecker,
+    pub fitness_cache: Option<Mutex<LruCache<u64, Evaluation>>>,  // Hash jadwal hasil decode -> evaluasi
+    pub cache_hits: usize,
+    pub cache_lookups: usize,
+    pub pause: Option<Arc<PauseControl>>,
+    pub job_id: Option<JobId>,  // Disertakan pada event progress
+    pub status: Option<Arc<Mutex<JobStatus>>>,  // Diperbarui tiap iterasi untuk get_status
+    pub resume_from: Option<SwarmState>,  // Dipakai sekali oleh optimize() sebagai titik awal
+    pub on_checkpoint: Option<CheckpointFn>,  // Dipanggil tiap checkpoint_every iterasi
+    pub convergence: Vec<f64>,  // Fitness global best setelah tiap iterasi pada run terakhir
+    pub best_breakdown: Option<BTreeMap<&'static str, f64>>,  // Breakdown global best untuk event progress, dikosongkan saat global best berubah
+}
+
+pub type CheckpointFn = Box<dyn Fn(&SwarmState) + Send + Sync>;
+
+// Snapshot swarm di tengah run, cukup untuk melanjutkan dari iterasi berikutnya
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SwarmState {
+    pub iteration: usize,  // Jumlah iterasi yang sudah selesai
+    pub particles: Vec<Particle>,
+    pub global_best_position: Vec<f64>,
+    pub global_best_fitness: f64,
+    pub global_best_evaluation: Evaluation,
+}
+
+// Semua yang dibutuhkan untuk melanjutkan job setelah aplikasi ditutup, disimpan bersama SwarmState
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Checkpoint {
+    pub id: String,
+    pub dibuat: DateTime<Local>,
+    pub course_csv: String,
+    pub preference_csv: String,
+    pub lecturer_csv: Option<String>,
+    pub absence_csv: Option<String>,
+    pub params: PsoParameters,
+    pub current_run: usize,
+    pub all_best_fitness: Vec<f64>,  // Run yang sudah selesai
+    pub run_durations: Vec<Duration>,
+    pub best_run: Option<(Vec<f64>, f64)>,  // Posisi dan fitness terbaik dari run yang sudah selesai
+}
+
+// Id job dari process_pso/tune_parameters, dipakai stop_pso/pause_pso/resume_pso
+pub type JobId = u64;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    Queued,  // Menunggu worker kosong pada server HTTP
+    Running,
+    Paused,
+    Finished,
+    Stopped,
+}
+
+// Isi get_status, agar frontend bisa memulihkan tampilan setelah reload
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct JobStatus {
+    #[cfg_attr(feature = "server", schema(value_type = u64))]
+    pub job_id: JobId,
+    pub state: JobState,
+    pub iteration: usize,       // Iterasi run saat ini, atau percobaan ke- saat tuning
+    pub max_iterations: usize,
+    pub current_run: usize,
+    pub total_runs: usize,
+    pub best_fitness: f64,      // Terbaik dari semua run sejauh ini
+    pub result: Option<serde_json::Value>,  // Sama dengan isi event "pso-finished" setelah selesai
+    pub checkpoint_id: Option<String>,  // Untuk resume_from_checkpoint bila aplikasi tertutup
+    pub started_at: DateTime<Local>,
+    pub finished_at: Option<DateTime<Local>>,
+}
+
+impl JobStatus {
+    /// Iterations done over all runs so far
+    pub fn iterations_done(&self) -> usize {
+        self.current_run * self.max_iterations + self.iteration
+    }
+}
+
+// Payload event dengan id job yang mengirimnya
+#[derive(Clone, Serialize)]
+pub struct JobEvent<T> {
+    pub job_id: JobId,
+    #[serde(flatten)]
+    pub data: T,
+}
+
+// Dijeda/dilanjutkan dari command pause_pso/resume_pso, state swarm tetap utuh selama jeda
+#[derive(Debug, Default)]
+pub struct PauseControl {
+    pub paused: Mutex<bool>,
+    pub resumed: Condvar,
+}
+
+pub const DEFAULT_CACHE_SIZE: usize = 4096;
+
+// (prodi, semester, id_kelas, id_waktu): the student group a course belongs to
+pub type ClassKey = (u32, u32, u32, u32);
+
+// Decoded class groups of a particle's last evaluation with the sort order they came from
+// and their group-local evaluation
+#[derive(Debug, Clone, Default)]
+pub struct DecodeCache {
+    pub signatures: Vec<Vec<usize>>,
+    pub groups: Vec<Vec<OptimizedCourse>>,
+    pub evaluations: Vec<Evaluation>,  // ScheduleChecker::evaluate_group per kelompok
 }
 
 #[derive(Debug, Clone)]
 pub struct ScheduleChecker {
-   pub time_preferences: HashMap<u32, TimePreferenceRequest>,
+   pub time_preferences: HashMap<u32, LecturerPreference>,
+   pub config: ConstraintConfig,
+   pub weights: PenaltyWeights,
+   pub custom_constraints: Vec<Arc<dyn Constraint>>,
+   pub parallel_conflicts_from: usize,  // Jumlah jadwal mulai konflik dicari paralel, default PARALLEL_CONFLICT_MIN_COURSES
+}
+
+pub const DEFAULT_MAX_SKS_KELAS_PER_HARI: u32 = 6;
+pub const DEFAULT_MAX_SKS_KELAS_4_PERTEMUAN: u32 = 3;  // Kelas dengan tepat 4 pertemuan: satu mata kuliah per hari
+pub const DEFAULT_HARI_AKTIF: [u32; 5] = [1, 2, 3, 4, 5];
+pub const DEFAULT_WAKTU: [TimeWindow; 2] = [
+    TimeWindow { id_waktu: 1, mulai: 480, akhir: 720 },   // Pagi: 08:00-12:00
+    TimeWindow { id_waktu: 2, mulai: 1080, akhir: 1320 }, // Malam: 18:00-22:00
+];
+pub const ID_WAKTU_MALAM: u32 = 2;  // Kelas malam, apa pun jam jendelanya di ConstraintConfig.waktu
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct ConstraintConfig {
+    pub max_sks_dosen_per_hari: Option<u32>,  // Batas SKS mengajar dosen per hari
+    pub max_sks_kelas_per_hari: Option<u32>,  // Batas SKS kelas per hari (default 6, atau 3 untuk kelas dengan 4 pertemuan)
+    #[serde(default)]
+    pub batas_sks_kelas: Vec<ClassSksLimit>,  // Override batas per semester/kelas
+    pub jam_istirahat: Option<TimeRange>,     // Jam istirahat harian, mis. 12:00-13:00
+    #[serde(default)]
+    pub blok_hari: Vec<DayBlock>,             // Blok khusus per hari, mis. sholat Jumat
+    pub bobot_jeda_kelas: Option<u32>,        // Penalti per jam jeda kosong mahasiswa
+    pub bobot_jeda_dosen: Option<u32>,        // Penalti per jam jeda dosen di atas toleransi
+    pub bobot_hari_dosen: Option<u32>,        // Penalti per hari datang ke kampus di atas minimum
+    pub toleransi_jeda_dosen: Option<u32>,    // Jeda dosen (menit) yang masih diterima
+    pub hari_aktif: Option<Vec<u32>>,         // Hari yang boleh dijadwalkan (1=Senin..7=Minggu), default Senin-Jumat
+    pub waktu: Option<Vec<TimeWindow>>,       // Rentang jam per id_waktu, default pagi & malam
+    #[serde(default)]
+    pub slot_terlarang: Vec<ForbiddenSlot>,   // Kombinasi hari/waktu yang dilarang per jadwal
+    #[serde(default)]
+    pub grup_slot_sama: Vec<Vec<u32>>,        // Kelompok id_jadwal yang harus berjalan bersamaan
+    #[serde(default)]
+    pub urutan_hari: Vec<Precedence>,         // Pasangan teori/praktikum yang harus berurutan
+    pub bobot_sebaran: Option<u32>,           // Penalti per pasangan seksi matkul yang sama di hari yang sama
+    #[serde(default)]
+    pub kohort: Vec<Cohort>,                  // Jadwal yang diikuti mahasiswa yang sama lintas kelas
+    #[serde(default)]
+    pub ruangan: Vec<Room>,                   // Data ruangan beserta gedungnya
+    pub jeda_pindah_gedung: Option<u32>,      // Jeda minimal (menit) saat pindah gedung
+    pub jeda_min_dosen: Option<u32>,          // Jeda minimal (menit) antar dua sesi dosen yang berurutan
+    pub bobot_keseimbangan: Option<u32>,      // Penalti per SKS selisih dari sebaran merata per hari
+    pub bobot_utilisasi_ruangan: Option<u32>, // Penalti per jam selisih pemakaian ruangan dari rata-rata
+    pub batas_pecah_sks: Option<u32>,         // Mata kuliah dengan SKS >= batas ini dipecah menjadi dua pertemuan
+    #[serde(default)]
+    pub jadwal_sebelumnya: Vec<PreviousPlacement>,  // Mis. hasil semester/run sebelumnya
+    pub bobot_stabilitas: Option<u32>,        // Penalti per jadwal yang berpindah hari/jam
+    pub kalender: Option<SemesterCalendar>,   // Dibutuhkan untuk mencocokkan jadwal mingguan dengan absen dosen
+    #[serde(default)]
+    pub ruangan_online: bool,                 // Kelas daring tetap memakai ruangannya (mis. studio) dan ikut cek ruangan
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct SemesterCalendar {
+    pub tanggal_mulai: NaiveDate,
+    pub jumlah_minggu: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct Room {
+    pub id_ruangan: u32,
+    pub gedung: u32,
 }
 
+// Bisa langsung diisi dengan `schedule` dari hasil optimasi sebelumnya
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct PreviousPlacement {
+    pub id_jadwal: u32,
+    #[serde(default)]
+    pub pertemuan: u32,
+    pub hari: u32,
+    pub jam_mulai: u32,
+}
+
+// Selisih dua kandidat jadwal, b dibandingkan terhadap a
+#[derive(Debug, Serialize, Clone)]
+pub struct ScheduleComparison {
+    pub fitness_a: f64,
+    pub fitness_b: f64,
+    pub dipindah: Vec<MovedCourse>,
+    pub hanya_di_a: Vec<u32>,
+    pub hanya_di_b: Vec<u32>,
+    pub penalti: Vec<PenaltyChange>,
+    pub kepuasan: Vec<SatisfactionChange>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MovedCourse {
+    pub id_jadwal: u32,
+    pub pertemuan: u32,
+    pub dari_hari: u32,
+    pub dari_jam: u32,
+    pub dari_ruangan: u32,
+    pub ke_hari: u32,
+    pub ke_jam: u32,
+    pub ke_ruangan: u32,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PenaltyChange {
+    pub nama: &'static str,
+    pub a: f64,
+    pub b: f64,
+    pub selisih: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SatisfactionChange {
+    pub id_dosen: u32,
+    pub a: f64,  // Persen sesi pada slot prefer
+    pub b: f64,
+    pub selisih: f64,
+}
+
+// Perubahan manual untuk dicoba lewat try_swap. Tanpa `pertemuan` yang dipakai pertemuan
+// pertama dari jadwal tersebut
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum ScheduleEdit {
+    // Tukar hari, jam dan ruangan dua jadwal; durasi masing-masing tetap
+    Swap {
+        id_a: u32,
+        id_b: u32,
+        #[serde(default)]
+        pertemuan_a: Option<u32>,
+        #[serde(default)]
+        pertemuan_b: Option<u32>,
+    },
+    // Pindahkan satu jadwal ke hari/jam lain, ruangan tetap
+    Move {
+        id_jadwal: u32,
+        hari: u32,
+        jam_mulai: u32,
+        #[serde(default)]
+        pertemuan: Option<u32>,
+    },
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueLevel {
+    Error,    // Optimasi tidak akan menghasilkan jadwal yang benar
+    Warning,  // Masih bisa dijalankan, tapi kemungkinan salah input
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ValidationIssue {
+    pub tingkat: IssueLevel,
+    pub kode: &'static str,  // Mis. "duplicate_id_jadwal", "zero_sks"
+    pub id_jadwal: Option<u32>,
+    pub pesan: String,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ValidationReport {
+    pub valid: bool,  // Tidak ada issue bertingkat error
+    pub issues: Vec<ValidationIssue>,
+}
+
+// Penjelasan kenapa satu jadwal (atau satu pertemuannya) mendapat slotnya
+#[derive(Debug, Serialize, Clone)]
+pub struct PlacementExplanation {
+    pub id_jadwal: u32,
+    pub pertemuan: u32,
+    pub hari: u32,
+    pub jam_mulai: u32,
+    pub jam_akhir: u32,
+    pub dikunci: bool,            // Hari/jam ditetapkan di input
+    pub day_order: f64,
+    pub time_order: f64,
+    pub urutan_hari: usize,       // Peringkat day_order di kelompok kelasnya, mulai 1
+    pub jumlah_kelompok: usize,
+    pub hari_penuh: Vec<DayLoad>, // Hari aktif sebelumnya yang sudah mencapai batas SKS
+    pub alternatif: Vec<AlternativeSlot>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DayLoad {
+    pub hari: u32,
+    pub sks: u32,
+    pub max_sks: u32,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AlternativeSlot {
+    pub hari: u32,
+    pub jam_mulai: u32,
+    pub konflik: Vec<u32>,  // id_jadwal yang akan bentrok bila dipindah ke sini
+}
+
+// Event "tuning-progress", dikirim setiap satu percobaan parameter selesai
+#[derive(Clone, Serialize)]
+pub struct TuningProgress {
+    pub parameter: String,
+    pub trial: usize,
+    pub total_trials: usize,
+    pub value: f64,
+    pub fitness: f64,   // Rata-rata bila satu percobaan diulang (repeats_per_trial)
+    pub std_dev: f64,
+    pub best_fitness: f64,
+    pub elapsed_seconds: f64,
+    pub eta_seconds: Option<f64>,  // Perkiraan sisa waktu dari rata-rata durasi percobaan
+}
+
+// Bagian variansi fitness antar percobaan tuning yang dijelaskan satu parameter (0..1)
+#[derive(Debug, Clone, Serialize)]
+pub struct ParameterSensitivity {
+    pub parameter: String,
+    pub variance_explained: f64,
+    pub groups: usize,  // Kelompok nilai yang dibandingkan
+}
+
+// Ringkasan fitness beberapa run (num_runs > 1)
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct RunStatistics {
+    pub mean: f64,
+    pub std_dev: f64,
+    pub median: f64,
+    pub best: f64,
+    pub worst: f64,
+    pub durations: Vec<f64>,  // Detik per run
+}
+
+// Satu entri riwayat optimasi, disimpan sebagai <id>.json di folder runs pada app data
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RunRecord {
+    pub id: String,
+    pub dibuat: DateTime<Local>,
+    pub input_hash: String,  // Hash CSV masukan, sama berarti data yang sama
+    pub params: PsoParameters,
+    pub fitness: f64,
+    pub durasi: f64,  // Detik, semua run dijumlahkan
+    pub schedule: Vec<OptimizedCourse>,
+}
+
+// Isi list_runs, tanpa jadwal agar daftarnya tetap ringan
+#[derive(Debug, Serialize, Clone)]
+pub struct RunSummary {
+    pub id: String,
+    pub dibuat: DateTime<Local>,
+    pub input_hash: String,
+    pub fitness: f64,
+    pub durasi: f64,
+    pub jumlah_jadwal: usize,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct PreferenceSatisfaction {
+    pub persen: f64,  // Persentase sesi yang jatuh pada slot prefer
+    pub per_dosen: Vec<LecturerSatisfaction>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LecturerSatisfaction {
+    pub id_dosen: u32,
+    pub jumlah_jadwal: u32,
+    pub jumlah_prefer: u32,
+    pub persen: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RoomUsage {
+    pub id_ruangan: u32,
+    pub gedung: u32,
+    pub jumlah_jadwal: u32,
+    pub menit_terpakai: u32,
+    pub persentase: f64,  // Terhadap total menit aktif per minggu
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct Cohort {
+    pub id_kohort: u32,
+    pub id_jadwal: Vec<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct Precedence {
+    pub sebelum: u32,  // id_jadwal yang harus lebih dulu dalam seminggu
+    pub sesudah: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct ForbiddenSlot {
+    pub id_jadwal: u32,
+    pub hari: u32,
+    pub id_waktu: Option<u32>,  // Kosong berarti seharian
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct TimeWindow {
+    pub id_waktu: u32,
+    pub mulai: u32,
+    pub akhir: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct DayBlock {
+    pub hari: u32,
+    pub mulai: u32,
+    pub akhir: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct TimeRange {
+    pub mulai: u32,  // Menit sejak 00:00
+    pub akhir: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct ClassSksLimit {
+    pub semester: u32,
+    pub id_kelas: Option<u32>,  // Kosong berarti berlaku untuk semua kelas di semester tsb
+    pub max_sks: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 pub struct PsoParameters {
     pub swarm_size: i32,
     pub max_iterations: usize,
     pub cognitive_weight: f64,
     pub social_weight: f64,
     pub inertia_weight: f64,
-    pub num_runs: Option<usize>
+    pub num_runs: Option<usize>,
+    #[serde(default)]
+    pub constraints: ConstraintConfig,
+    #[serde(default)]
+    pub penalty_weights: PenaltyWeights,
+    #[serde(default)]
+    pub cache_size: Option<usize>,  // Kapasitas cache fitness, 0 = nonaktif
+    #[serde(default)]
+    pub emit_every: Option<usize>,  // Kirim progress tiap n iterasi (default 1), event akhir selalu dikirim
+    #[serde(default)]
+    pub checkpoint_every: Option<usize>,  // Simpan checkpoint tiap n iterasi, kosong = nonaktif
+    #[serde(default)]
+    pub max_velocity: Option<f64>,  // Batas |kecepatan| per dimensi, kosong = tanpa batas
+    #[serde(default)]
+    pub mutation_rate: Option<f64>,  // Peluang posisi satu dimensi diacak ulang tiap iterasi
+    #[serde(default)]
+    pub seed: Option<u64>,  // Seed acak, seed sama = hasil sama; kosong = acak tiap run
+}
+
+// Bobot penalti per jenis pelanggaran, default 100 seperti sebelumnya
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct PenaltyWeights {
+    pub dosen_conflict: u32,
+    pub room_conflict: u32,
+    pub class_conflict: u32,
+    pub cohort_conflict: u32,
+    pub preference: u32,
+    pub neutral_preference: u32,
+    pub back_to_back: u32,
+    pub dosen_daily_load: u32,
+    pub dosen_weekly_load: u32,
+    pub evening_load: u32,
+    pub class_daily_load: u32,
+    pub blocked_time: u32,
+    pub inactive_day: u32,
+    pub forbidden_slot: u32,
+    pub same_slot: u32,
+    pub split_same_day: u32,
+    pub precedence: u32,
+    pub building_travel: u32,
+    pub absence: u32,  // Per pertemuan yang bertabrakan dengan absen dosen
+    pub dosen_break: u32,
+    pub scale_conflicts_by_overlap: bool,  // Konflik dibobot menit tumpang tindih dan total SKS
+}
+
+impl Default for PenaltyWeights {
+    fn default() -> Self {
+        Self {
+            dosen_conflict: 100,
+            room_conflict: 100,
+            class_conflict: 100,
+            cohort_conflict: 100,
+            preference: 100,
+            neutral_preference: 25,
+            back_to_back: 100,
+            dosen_daily_load: 100,
+            dosen_weekly_load: 100,
+            evening_load: 100,
+            class_daily_load: 100,
+            blocked_time: 100,
+            inactive_day: 100,
+            forbidden_slot: 100,
+            same_slot: 100,
+            split_same_day: 100,
+            precedence: 100,
+            building_travel: 100,
+            absence: 25,
+            dosen_break: 100,
+            scale_conflicts_by_overlap: false,
+        }
+    }
 }
 
 
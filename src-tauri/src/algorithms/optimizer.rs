@@ -1,24 +1,41 @@
 use std::{
-    sync::{Arc, atomic::{AtomicBool, Ordering}},
-    time::Instant,
-    collections::HashMap,
+    sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}},
+    time::{Duration, Instant},
+    collections::{BTreeMap, HashMap},
+    hash::{DefaultHasher, Hash, Hasher},
+    num::NonZeroUsize,
 };
 
-use rand::Rng;
+use lru::LruCache;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use rayon::prelude::*;
 use tauri::{Emitter, Window};
 
+use super::error::ScheduleError;
 use super::{models::{
-        CourseRequest, OptimizationProgress, OptimizedCourse, Particle, PsoParameters, ScheduleChecker, TimePreferenceRequest, PSO
+        ClassKey, ConstraintConfig, CourseRequest, DecodeCache, Evaluation, OptimizationProgress, OptimizedCourse, Particle, PauseControl, PsoParameters, RunStatistics, ScheduleChecker, SwarmState, LecturerPreference, TimeRange, PSO,
+        AlternativeSlot, DayLoad, PlacementExplanation, DEFAULT_CACHE_SIZE, SLOT_MINUTES,
 }};
 
+// Booked minutes and (hari, jam_mulai, jam_akhir) sessions of a room
+type RoomBookings = (u32, Vec<(u32, u32, u32)>);
+
+/// Seed of the `index`-th sub-run of a seeded run (SplitMix64), so neighbouring indexes still
+/// give unrelated random streams
+pub fn derive_seed(seed: u64, index: u64) -> u64 {
+    let mut z = seed.wrapping_add(index.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
 // ============================================================================
 // PARTICLE IMPLEMENTATION
 // ============================================================================
 impl Particle {
-    /// Create new particle with random position and velocity
-    pub fn new(dimension: usize) -> Self {
-        let mut rng = rand::rng();
+    /// Create new particle with random position and velocity, drawn like all its later
+    /// moves from its own `rng` so a seeded swarm does not depend on thread scheduling
+    pub fn new(dimension: usize, mut rng: StdRng) -> Self {
         
         // Random position in [0,1] range
         let position: Vec<f64> = (0..dimension)
@@ -36,6 +53,10 @@ impl Particle {
             pbest_position: vec![0.0; dimension], // Will be set after first evaluation
             pbest_fitness: f64::INFINITY,        // Initialize with infinity
             fitness: f64::INFINITY,              // Will be calculated in first iteration
+            evaluation: Evaluation::default(),
+            pbest_evaluation: Evaluation::default(),
+            cache: DecodeCache::default(),
+            rng,
         }
     }
 
@@ -47,11 +68,9 @@ impl Particle {
         cognitive_weight: f64,
         social_weight: f64,
     ) {
-        let mut rng = rand::rng();
-        
         for i in 0..self.velocity.len() {
-            let r1: f64 = rng.random(); 
-            let r2: f64 = rng.random(); 
+            let r1: f64 = self.rng.random(); 
+            let r2: f64 = self.rng.random(); 
             
             let cognitive = cognitive_weight * r1 * (self.pbest_position[i] - self.position[i]);
             
@@ -62,6 +81,23 @@ impl Particle {
         }
     }
 
+    /// Keep every velocity component within [-max_velocity, max_velocity]
+    pub fn clamp_velocity(&mut self, max_velocity: f64) {
+        for v in &mut self.velocity {
+            *v = v.clamp(-max_velocity, max_velocity);
+        }
+    }
+
+    /// Re-randomize each position component with probability `rate`, so a converged swarm
+    /// still explores
+    pub fn mutate(&mut self, rate: f64) {
+        for x in &mut self.position {
+            if self.rng.random_bool(rate) {
+                *x = self.rng.random_range(0.0..1.0);
+            }
+        }
+    }
+
     pub fn update_position(&mut self) {
         for i in 0..self.position.len() {
             self.position[i] += self.velocity[i];
@@ -73,6 +109,7 @@ impl Particle {
         if self.fitness < self.pbest_fitness && !self.fitness.is_nan() {
             self.pbest_fitness = self.fitness;
             self.pbest_position = self.position.clone();
+            self.pbest_evaluation = self.evaluation;
         }
     }
 }
@@ -84,18 +121,36 @@ impl PSO {
     /// Constructor for new PSO instance
     pub fn new(
         courses: Vec<CourseRequest>,
-        time_preferences: Vec<TimePreferenceRequest>,
+        time_preferences: Vec<LecturerPreference>,
         parameters: PsoParameters,
     ) -> Self {
+        let courses = Self::split_courses(courses, parameters.constraints.batas_pecah_sks);
         let dimension = courses.len() * 2; // 2 dimensions per course: day_order, time_order
 
         PSO {
             particles: vec![],
             global_best_position: vec![0.0; dimension],
             global_best_fitness: f64::INFINITY,
+            global_best_evaluation: Evaluation::default(),
+            groups: Self::class_groups(&courses),
             courses,
+            checker: ScheduleChecker::new(
+                time_preferences,
+                parameters.constraints.clone(),
+                parameters.penalty_weights.clone(),
+            ),
+            fitness_cache: NonZeroUsize::new(parameters.cache_size.unwrap_or(DEFAULT_CACHE_SIZE))
+                .map(|size| Mutex::new(LruCache::new(size))),
+            cache_hits: 0,
+            cache_lookups: 0,
+            pause: None,
+            job_id: None,
+            status: None,
+            resume_from: None,
+            on_checkpoint: None,
+            convergence: Vec::new(),
+            best_breakdown: None,
             parameters,
-            checker: ScheduleChecker::new(time_preferences),
         }
     }
 
@@ -110,33 +165,48 @@ impl PSO {
         let start_time = Instant::now();
         let (current_run, total_runs) = run_info.unwrap_or((0, 0));
 
-        // Reset state for new run
-        self.reset_optimization();
-
-        // Initialize swarm with random particles
-        self.initialize_swarm();
+        // Continue from a checkpoint, or reset state and start a new random swarm
+        let start_iteration = match self.resume_from.take() {
+            Some(swarm) => self.restore(swarm),
+            None => {
+                self.start(current_run);
+                0
+            }
+        };
 
         // Main optimization loop
-        for iteration in 0..self.parameters.max_iterations {
+        for iteration in start_iteration..self.parameters.max_iterations {
             
+            if let Some(pause) = &self.pause {
+                pause.wait_while_paused(&stop_flag);
+            }
+
             if stop_flag.load(Ordering::Relaxed) {
                 break;
             }
 
-            // Step 1: Evaluate all particles
-            self.evaluate_all_particles();
+            self.step();
 
-            // Step 2: Update global best
-            self.update_global_best();
-
-            // Step 3: Update all particles (velocity and position)
-            self.update_all_particles();
+            if let Some(status) = &self.status {
+                let mut status = status.lock().unwrap();
+                status.iteration = iteration + 1;
+                status.max_iterations = self.parameters.max_iterations;
+                status.current_run = current_run;
+                status.total_runs = total_runs;
+                status.best_fitness = status.best_fitness.min(self.global_best_fitness);
+            }
 
-            // Progress reporting
-            if let Some(w) = window {
+            // Progress reporting, throttled so long runs don't flood the IPC channel
+            let emit_every = self.parameters.emit_every.unwrap_or(1).max(1);
+            if let Some(w) = window.filter(|_| (iteration + 1) % emit_every == 0) {
                 self.emit_progress(Some(w), iteration + 1, &start_time, all_best_fitness, current_run, total_runs, false);
             }
 
+            let checkpoint_every = self.parameters.checkpoint_every.unwrap_or(0);
+            if let Some(save) = self.on_checkpoint.as_ref().filter(|_| checkpoint_every > 0 && (iteration + 1) % checkpoint_every == 0) {
+                save(&self.swarm_state(iteration + 1));
+            }
+
             // Early stopping for very good solutions
             if self.global_best_fitness < 0.001 {
                 break;
@@ -152,32 +222,154 @@ impl PSO {
         (self.global_best_position.clone(), self.global_best_fitness)
     }
 
+    /// Best schedule of `params.num_runs` runs and its fitness, for callers without checkpoints
+    /// or a window. `hooks` sets up each run's PSO before it starts, e.g. its job status.
+    pub async fn optimize_best(
+        courses: Vec<CourseRequest>,
+        time_preferences: Vec<LecturerPreference>,
+        params: &PsoParameters,
+        stop_flag: Arc<AtomicBool>,
+        hooks: impl Fn(&mut PSO),
+    ) -> Result<(Vec<OptimizedCourse>, f64), ScheduleError> {
+        let num_runs = params.num_runs.unwrap_or(1);
+        let mut all_best_fitness = Vec::with_capacity(num_runs);
+        let mut best: Option<(Vec<OptimizedCourse>, f64)> = None;
+
+        for i in 0..num_runs {
+            let mut pso = PSO::new(courses.clone(), time_preferences.clone(), params.clone());
+            hooks(&mut pso);
+
+            let (position, fitness) = pso.optimize(None, Some((i, num_runs)), &mut all_best_fitness, stop_flag.clone()).await;
+            if best.as_ref().is_none_or(|(_, best_fitness)| fitness < *best_fitness) {
+                best = Some((Self::position_to_schedule(&position, &pso.courses, &params.constraints), fitness));
+            }
+
+            if stop_flag.load(Ordering::Relaxed) {
+                break;
+            }
+        }
+
+        best.ok_or(ScheduleError::Stopped)
+    }
+
     /// Reset optimization state for new run
     fn reset_optimization(&mut self) {
         self.global_best_fitness = f64::INFINITY;
+        self.global_best_evaluation = Evaluation::default();
         self.global_best_position.fill(0.0);
         self.particles.clear();
+        self.cache_hits = 0;
+        self.cache_lookups = 0;
+        self.convergence.clear();
+        self.best_breakdown = None;
+    }
+
+    /// Snapshot for a checkpoint after `iteration` completed iterations
+    pub fn swarm_state(&self, iteration: usize) -> SwarmState {
+        SwarmState {
+            iteration,
+            particles: self.particles.clone(),
+            global_best_position: self.global_best_position.clone(),
+            global_best_fitness: self.global_best_fitness,
+            global_best_evaluation: self.global_best_evaluation,
+        }
+    }
+
+    /// Restore a checkpointed swarm, returns the iteration to continue from
+    fn restore(&mut self, swarm: SwarmState) -> usize {
+        self.particles = swarm.particles;
+        self.global_best_position = swarm.global_best_position;
+        self.global_best_fitness = swarm.global_best_fitness;
+        self.global_best_evaluation = swarm.global_best_evaluation;
+        self.best_breakdown = None;
+        self.cache_hits = 0;
+        self.cache_lookups = 0;
+        swarm.iteration
+    }
+
+    /// Fresh random swarm for run number `run`, with the best-so-far state cleared
+    pub fn start(&mut self, run: usize) {
+        self.reset_optimization();
+        self.initialize_swarm(run);
+    }
+
+    /// One PSO iteration: evaluate, update the global best, then move every particle
+    pub fn step(&mut self) {
+        // Step 1: Evaluate all particles
+        self.evaluate_all_particles();
+
+        // Step 2: Update global best
+        self.update_global_best();
+        self.convergence.push(self.global_best_fitness);
+
+        // Step 3: Update all particles (velocity and position)
+        self.update_all_particles();
     }
 
-    /// Initialize swarm with random particles (no fitness evaluation here)
-    fn initialize_swarm(&mut self) {
+    /// Initialize swarm with random particles (no fitness evaluation here).
+    /// With a seed every run of a job gets its own, derived from the run number.
+    fn initialize_swarm(&mut self, run: usize) {
         let dimension = self.courses.len() * 2;
-        
+        let mut swarm_rng = match self.parameters.seed {
+            Some(seed) => StdRng::seed_from_u64(derive_seed(seed, run as u64)),
+            None => StdRng::from_rng(&mut rand::rng()),
+        };
+
         self.particles = (0..self.parameters.swarm_size)
-            .map(|_| Particle::new(dimension))
+            .map(|_| Particle::new(dimension, StdRng::from_rng(&mut swarm_rng)))
             .collect();
     }
 
     /// Evaluate fitness for all particles
     fn evaluate_all_particles(&mut self) {
-        let courses = self.courses.clone();
-        let checker = self.checker.clone();
-
-        self.particles.par_iter_mut().for_each(|particle| {
-            let schedule = Self::position_to_schedule(&particle.position, &courses);
-            particle.fitness = checker.evaluate(&schedule);
+        let courses = &self.courses;
+        let groups = &self.groups;
+        let checker = &self.checker;
+        let cache = &self.fitness_cache;
+
+        let lookups: Vec<bool> = self.particles.par_iter_mut().filter_map(|particle| {
+            // An unchanged decoding keeps the previous evaluation
+            let hit = Self::decode_incremental(particle, courses, groups, checker).map(|schedule| {
+                let Some(cache) = cache else {
+                    particle.evaluation = Self::evaluate_decoded(&particle.cache, checker, &schedule);
+                    return false;
+                };
+
+                // Identical decodings of other particles or iterations are looked up instead
+                let key = Self::schedule_hash(&schedule);
+                let cached = cache.lock().unwrap().get(&key).copied();
+                match cached {
+                    Some(evaluation) => particle.evaluation = evaluation,
+                    None => {
+                        particle.evaluation = Self::evaluate_decoded(&particle.cache, checker, &schedule);
+                        cache.lock().unwrap().put(key, particle.evaluation);
+                    }
+                }
+                cached.is_some()
+            });
+            particle.fitness = particle.evaluation.fitness();
             particle.update_personal_best();
-        });
+            hit
+        }).collect();
+
+        if self.fitness_cache.is_some() {
+            self.cache_lookups += lookups.len();
+            self.cache_hits += lookups.iter().filter(|&&hit| hit).count();
+        }
+    }
+
+    /// Hash of the (day, slot, room) assignment, taken over the placements in sorted order so
+    /// the order of the schedule does not matter
+    fn schedule_hash(schedule: &[OptimizedCourse]) -> u64 {
+        let mut placements: Vec<_> = schedule
+            .iter()
+            .map(|course| (course.id_jadwal, course.pertemuan, course.hari, course.jam_mulai, course.ruangan))
+            .collect();
+        placements.sort_unstable();
+
+        let mut hasher = DefaultHasher::new();
+        placements.hash(&mut hasher);
+        hasher.finish()
     }
 
     /// Update global best from all particles
@@ -186,6 +378,8 @@ impl PSO {
             if particle.pbest_fitness < self.global_best_fitness && !particle.pbest_fitness.is_nan() {
                 self.global_best_fitness = particle.pbest_fitness;
                 self.global_best_position = particle.pbest_position.clone();
+                self.global_best_evaluation = particle.pbest_evaluation;
+                self.best_breakdown = None;
             }
         }
     }
@@ -204,13 +398,19 @@ impl PSO {
                 params.cognitive_weight,
                 params.social_weight,
             );
+            if let Some(max_velocity) = params.max_velocity.filter(|v| *v > 0.0) {
+                particle.clamp_velocity(max_velocity);
+            }
             particle.update_position();
+            if let Some(rate) = params.mutation_rate.filter(|r| *r > 0.0) {
+                particle.mutate(rate.min(1.0));
+            }
         });
     }
 
     /// Emit progress to frontend
     fn emit_progress(
-        &self,
+        &mut self,
         window: Option<&Window>,
         iteration: usize,
         start_time: &Instant,
@@ -220,6 +420,14 @@ impl PSO {
         is_finished: bool,
     ) {
         if let Some(window) = window {
+            // Decoding and checking the best schedule again is only needed after it changed
+            let breakdown = self
+                .best_breakdown
+                .get_or_insert_with(|| {
+                    let best_schedule = Self::position_to_schedule(&self.global_best_position, &self.courses, &self.checker.config);
+                    self.checker.breakdown(&best_schedule)
+                })
+                .clone();
             let _ = window.emit(
                 "optimization-progress",
                 OptimizationProgress {
@@ -230,6 +438,15 @@ impl PSO {
                     current_run: Some(current_run),
                     total_runs: Some(total_runs),
                     is_finished,
+                    hard_violations: self.global_best_evaluation.hard_violations,
+                    soft_penalty: self.global_best_evaluation.soft_penalty,
+                    breakdown,
+                    cache_hit_rate: if self.cache_lookups == 0 {
+                        0.0
+                    } else {
+                        self.cache_hits as f64 / self.cache_lookups as f64
+                    },
+                    job_id: self.job_id,
                 },
             );
         }
@@ -239,105 +456,219 @@ impl PSO {
     pub fn position_to_schedule(
         position: &[f64],
         courses: &[CourseRequest],
+        config: &ConstraintConfig,
     ) -> Vec<OptimizedCourse> {
-        let mut grouped: HashMap<(u32, u32, u32, u32), Vec<(f64, f64, OptimizedCourse)>> = HashMap::new();
+        let mut final_schedule: Vec<OptimizedCourse> = Self::class_groups(courses)
+            .iter()
+            .flat_map(|indices| Self::decode_group(position, courses, indices, config))
+            .collect();
+
+        Self::assign_rooms(&mut final_schedule, config);
+
+        final_schedule
+    }
 
-        // Group courses by prodi, semester, class, and time
+    /// Course indices grouped by prodi, semester, class, and time, in key order.
+    /// Decoding never looks across groups, so each can be decoded on its own, but rooms are
+    /// assigned in schedule order, so the group order has to be the same on every call.
+    pub fn class_groups(courses: &[CourseRequest]) -> Vec<Vec<usize>> {
+        let mut grouped: BTreeMap<ClassKey, Vec<usize>> = BTreeMap::new();
         for (i, course) in courses.iter().enumerate() {
+            let key = (course.prodi, course.semester, course.id_kelas, course.id_waktu);
+            grouped.entry(key).or_default().push(i);
+        }
+
+        grouped.into_values().collect()
+    }
+
+    /// Decode and re-check only the groups whose day/time order changed since the particle's last
+    /// evaluation. Returns None when the decoded schedule is identical, so the cached evaluation still holds.
+    fn decode_incremental(
+        particle: &mut Particle,
+        courses: &[CourseRequest],
+        groups: &[Vec<usize>],
+        checker: &ScheduleChecker,
+    ) -> Option<Vec<OptimizedCourse>> {
+        let cache = &mut particle.cache;
+        if cache.signatures.len() != groups.len() {
+            cache.signatures = vec![vec![]; groups.len()];
+            cache.groups = vec![vec![]; groups.len()];
+            cache.evaluations = vec![Evaluation::default(); groups.len()];
+        }
+
+        let mut changed = false;
+        for (g, indices) in groups.iter().enumerate() {
+            let signature = Self::group_signature(&particle.position, indices);
+            if signature.is_empty() || signature != cache.signatures[g] {
+                cache.groups[g] = Self::decode_group(&particle.position, courses, indices, &checker.config);
+                cache.evaluations[g] = checker.evaluate_group(&cache.groups[g]);
+                cache.signatures[g] = signature;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            return None;
+        }
+
+        // Same group order as position_to_schedule, so rooms are handed out the same way
+        let mut schedule: Vec<OptimizedCourse> = cache.groups.concat();
+        Self::assign_rooms(&mut schedule, &checker.config);
+        Some(schedule)
+    }
+
+    /// Same as `checker.evaluate(schedule)` for a schedule from `decode_incremental`, with the
+    /// group-local part taken from the decode cache
+    fn evaluate_decoded(cache: &DecodeCache, checker: &ScheduleChecker, schedule: &[OptimizedCourse]) -> Evaluation {
+        cache.evaluations.iter().fold(checker.evaluate_across_groups(schedule), |total, &group| total + group)
+    }
+
+    /// Sort order of a group's day and time dimensions, the only thing decoding depends on
+    fn group_signature(position: &[f64], indices: &[usize]) -> Vec<usize> {
+        if indices.iter().any(|&i| i * 2 + 1 >= position.len()) {
+            return vec![];
+        }
+
+        let mut by_day: Vec<usize> = (0..indices.len()).collect();
+        by_day.sort_by(|&a, &b| position[indices[a] * 2].partial_cmp(&position[indices[b] * 2]).unwrap());
+        let mut by_time: Vec<usize> = (0..indices.len()).collect();
+        by_time.sort_by(|&a, &b| position[indices[a] * 2 + 1].partial_cmp(&position[indices[b] * 2 + 1]).unwrap());
+
+        by_day.extend(by_time);
+        by_day
+    }
+
+    /// Decode the days and times of one class group
+    fn decode_group(
+        position: &[f64],
+        courses: &[CourseRequest],
+        indices: &[usize],
+        config: &ConstraintConfig,
+    ) -> Vec<OptimizedCourse> {
+        let mut sorted: Vec<(f64, f64, OptimizedCourse)> = Vec::with_capacity(indices.len());
+        for &i in indices {
             let idx = i * 2;
             if idx + 1 >= position.len() {
-                break;
+                continue;
             }
 
             let day_order = position[idx];
             let time_order = position[idx + 1];
-            let key = (course.prodi, course.semester, course.id_kelas, course.id_waktu);
+            let course = &courses[i];
 
+            // Pinned courses keep their given day/time, 0 means "to be decoded"
             let opt_course = OptimizedCourse {
                 id_jadwal: course.id_jadwal,
                 id_matkul: course.id_matkul,
                 id_dosen: course.id_dosen,
                 id_kelas: course.id_kelas,
                 id_waktu: course.id_waktu,
-                hari: 0,
-                jam_mulai: 0,
-                jam_akhir: 0,
-                ruangan: 0,
+                hari: course.fixed_hari.unwrap_or(0),
+                jam_mulai: course.fixed_jam_mulai.unwrap_or(0),
+                jam_akhir: course.fixed_jam_mulai.map_or(0, |mulai| mulai + course.sks * 40),
+                // Online courses skip room assignment unless the config keeps their room
+                ruangan: if course.is_online && !config.ruangan_online {
+                    0
+                } else {
+                    course.id_ruangan.unwrap_or(0)
+                },
                 semester: course.semester,
                 sks: course.sks,
                 prodi: course.prodi,
+                is_online: course.is_online,
+                pertemuan: course.pertemuan,
             };
 
-            grouped.entry(key).or_default().push((day_order, time_order, opt_course));
+            sorted.push((day_order, time_order, opt_course));
         }
 
-        let mut scheduled = Vec::with_capacity(courses.len());
+        let Some((_, _, first)) = sorted.first() else {
+            return vec![];
+        };
+        let (semester, id_kelas, id_waktu) = (first.semester, first.id_kelas, first.id_waktu);
+
+        let days = config.active_days();
 
         // Schedule days based on day_order
-        for entries in grouped.into_values() {
-            let mut sorted = entries;
-            sorted.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        sorted.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
 
-            // SKS limit per day based on number of courses
-            let max_sks = if sorted.len() == 4 { 3 } else { 6 };
-            let mut sks_per_day = [0u32; 5]; // Monday-Friday
-            let mut current_day = 0;
+        // SKS limit per day for this class, overflow is penalized by the checker
+        let max_sks = config.max_sks_kelas(semester, id_kelas, sorted.len());
+        let mut sks_per_day = vec![0u32; days.len()];
+        let mut current_day = 0;
 
-            for (_, time_order, mut course) in sorted {
+        // Pinned courses take their share of the day first
+        for (_, _, course) in sorted.iter().filter(|(_, _, c)| c.hari != 0) {
+            if let Some(i) = days.iter().position(|&d| d == course.hari) {
+                sks_per_day[i] += course.sks;
+            }
+        }
+
+        let mut by_day: BTreeMap<u32, Vec<(f64, OptimizedCourse)>> = BTreeMap::new();
+        for (_, time_order, mut course) in sorted {
+            if course.hari == 0 {
                 // Find available day
-                while current_day < 5 {
+                while current_day < days.len() {
                     if sks_per_day[current_day] + course.sks <= max_sks {
-                        course.hari = current_day as u32 + 1; // 1=Monday, 2=Tuesday, etc.
+                        course.hari = days[current_day]; // 1=Monday, 2=Tuesday, etc.
                         sks_per_day[current_day] += course.sks;
                         break;
                     }
                     current_day += 1;
                 }
 
-                // Fallback to Friday if no slot available
+                // Fallback to the last active day if no slot available
                 if course.hari == 0 {
-                    course.hari = 5; // Friday
+                    course.hari = days[days.len() - 1];
                 }
-
-                scheduled.push((
-                    (course.prodi, course.semester, course.id_kelas, course.id_waktu, course.hari),
-                    time_order,
-                    course,
-                ));
             }
+
+            by_day.entry(course.hari).or_default().push((time_order, course));
         }
 
         // Schedule times based on time_order
-        let mut by_day: HashMap<_, Vec<_>> = HashMap::new();
-        for (key, time_order, course) in scheduled {
-            by_day.entry(key).or_default().push((time_order, course));
-        }
+        let mut final_schedule = Vec::with_capacity(indices.len());
 
-        let mut final_schedule = Vec::with_capacity(courses.len());
+        // Determine time range based on id_waktu
+        let TimeRange { mulai: start, akhir: end } = config.time_window(id_waktu);
 
-        for ((_, _, _, id_waktu, _), mut entries) in by_day {
+        for (hari, mut entries) in by_day {
             entries.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
 
-            // Determine time range based on id_waktu
-            let (start, end) = match id_waktu {
-                1 => (480, 720),   // Morning: 08:00-12:00 (in minutes)
-                2 => (1080, 1320), // Evening: 18:00-22:00 (in minutes)
-                _ => (480, 720),   // Default morning
-            };
-
+            // Pinned sessions are blocked out for the rest of the group
+            let mut blocked = config.blocked_ranges(hari);
+            blocked.extend(
+                entries
+                    .iter()
+                    .filter(|(_, c)| c.jam_akhir != 0)
+                    .map(|(_, c)| TimeRange { mulai: c.jam_mulai, akhir: c.jam_akhir }),
+            );
             let mut current_time = start;
+            let mut previous_dosen = None;
 
             for (_, mut course) in entries {
+                if course.jam_akhir != 0 {
+                    final_schedule.push(course);
+                    continue;
+                }
+
                 let duration = course.sks * 40; // 40 minutes per SKS
-                
+
+                // A lecturer teaching the class twice in a row gets the configured break in between
+                if let Some(gap) = config.jeda_min_dosen.filter(|_| previous_dosen == Some(course.id_dosen)) {
+                    current_time += gap;
+                }
+                current_time = Self::skip_blocked(current_time, duration, &blocked);
+
                 // Reset to start if not enough time
                 if current_time + duration > end {
-                    current_time = start;
+                    current_time = Self::skip_blocked(start, duration, &blocked);
                 }
 
                 course.jam_mulai = current_time;
                 course.jam_akhir = current_time + duration;
                 current_time += duration;
+                previous_dosen = Some(course.id_dosen);
 
                 final_schedule.push(course);
             }
@@ -345,4 +676,311 @@ impl PSO {
 
         final_schedule
     }
-}
\ No newline at end of file
+
+    /// Give each course without a room the least used configured room that is free at its time
+    fn assign_rooms(schedule: &mut [OptimizedCourse], config: &ConstraintConfig) {
+        if config.ruangan.is_empty() {
+            return;
+        }
+
+        let mut usage: HashMap<u32, RoomBookings> = HashMap::new();
+        for course in schedule.iter().filter(|c| c.ruangan != 0) {
+            let entry = usage.entry(course.ruangan).or_default();
+            entry.0 += course.jam_akhir - course.jam_mulai;
+            entry.1.push((course.hari, course.jam_mulai, course.jam_akhir));
+        }
+
+        for course in schedule.iter_mut() {
+            if course.ruangan != 0 || (course.is_online && !config.ruangan_online) {
+                continue;
+            }
+
+            let is_free = |id: u32| {
+                usage.get(&id).is_none_or(|(_, booked)| {
+                    booked.iter().all(|&(hari, mulai, akhir)| {
+                        hari != course.hari || course.jam_akhir <= mulai || akhir <= course.jam_mulai
+                    })
+                })
+            };
+            let minutes = |id: u32| usage.get(&id).map_or(0, |(m, _)| *m);
+
+            // A clash is left to the checker when every room is taken
+            let room = config
+                .ruangan
+                .iter()
+                .map(|r| r.id_ruangan)
+                .min_by_key(|&id| (!is_free(id), minutes(id)))
+                .unwrap();
+
+            let entry = usage.entry(room).or_default();
+            entry.0 += course.jam_akhir - course.jam_mulai;
+            entry.1.push((course.hari, course.jam_mulai, course.jam_akhir));
+            course.ruangan = room;
+        }
+    }
+
+    /// Explain the placement of every meeting of `id_jadwal` in a schedule decoded from `position`
+    pub fn explain(
+        position: &[f64],
+        courses: &[CourseRequest],
+        schedule: &[OptimizedCourse],
+        checker: &ScheduleChecker,
+        id_jadwal: u32,
+    ) -> Vec<PlacementExplanation> {
+        let config = &checker.config;
+        let days = config.active_days();
+
+        courses
+            .iter()
+            .enumerate()
+            .filter(|(i, c)| c.id_jadwal == id_jadwal && i * 2 + 1 < position.len())
+            .filter_map(|(i, course)| {
+                let placed = schedule.iter().find(|c| c.id_jadwal == id_jadwal && c.pertemuan == course.pertemuan)?;
+                let key: ClassKey = (course.prodi, course.semester, course.id_kelas, course.id_waktu);
+                let same_class = |c: &CourseRequest| (c.prodi, c.semester, c.id_kelas, c.id_waktu) == key;
+
+                // Rank of the day order within the class group, the decoder fills days in this order
+                let group: Vec<usize> = (0..courses.len()).filter(|&j| same_class(&courses[j]) && j * 2 < position.len()).collect();
+                let urutan_hari = 1 + group.iter().filter(|&&j| position[j * 2] < position[i * 2]).count();
+
+                // Earlier active days that could not take this course anymore
+                let max_sks = config.max_sks_kelas(course.semester, course.id_kelas, group.len());
+                let hari_penuh = days
+                    .iter()
+                    .take_while(|&&hari| hari != placed.hari)
+                    .filter_map(|&hari| {
+                        let sks: u32 = schedule
+                            .iter()
+                            .filter(|c| c.hari == hari && (c.prodi, c.semester, c.id_kelas, c.id_waktu) == key)
+                            .map(|c| c.sks)
+                            .sum();
+                        (sks + course.sks > max_sks).then_some(DayLoad { hari, sks, max_sks })
+                    })
+                    .collect();
+
+                // Every other start in the class's time window, with the courses it would clash with
+                let duration = placed.jam_akhir - placed.jam_mulai;
+                let window = config.time_window(course.id_waktu);
+                let alternatif = days
+                    .iter()
+                    .flat_map(|&hari| {
+                        (window.mulai..=window.akhir.saturating_sub(duration))
+                            .step_by(SLOT_MINUTES as usize)
+                            .map(move |jam_mulai| (hari, jam_mulai))
+                    })
+                    .filter(|&(hari, jam_mulai)| (hari, jam_mulai) != (placed.hari, placed.jam_mulai))
+                    .map(|(hari, jam_mulai)| {
+                        let candidate = OptimizedCourse { hari, jam_mulai, jam_akhir: jam_mulai + duration, ..placed.clone() };
+                        AlternativeSlot { hari, jam_mulai, konflik: checker.conflicting_courses(schedule, &candidate) }
+                    })
+                    .collect();
+
+                Some(PlacementExplanation {
+                    id_jadwal,
+                    pertemuan: course.pertemuan,
+                    hari: placed.hari,
+                    jam_mulai: placed.jam_mulai,
+                    jam_akhir: placed.jam_akhir,
+                    dikunci: course.fixed_hari.is_some() || course.fixed_jam_mulai.is_some(),
+                    day_order: position[i * 2],
+                    time_order: position[i * 2 + 1],
+                    urutan_hari,
+                    jumlah_kelompok: group.len(),
+                    hari_penuh,
+                    alternatif,
+                })
+            })
+            .collect()
+    }
+
+    /// Split courses of at least `threshold` SKS into two meetings sharing their id_jadwal
+    pub fn split_courses(courses: Vec<CourseRequest>, threshold: Option<u32>) -> Vec<CourseRequest> {
+        let Some(threshold) = threshold else {
+            return courses;
+        };
+
+        let mut split = Vec::with_capacity(courses.len());
+        for course in courses {
+            if course.sks < threshold.max(2) || course.pertemuan != 0 {
+                split.push(course);
+                continue;
+            }
+
+            // The second meeting is left for the decoder to place, only the first keeps a pin
+            let second = CourseRequest {
+                sks: course.sks / 2,
+                pertemuan: 2,
+                fixed_hari: None,
+                fixed_jam_mulai: None,
+                ..course.clone()
+            };
+            split.push(CourseRequest {
+                sks: course.sks - second.sks,
+                pertemuan: 1,
+                ..course
+            });
+            split.push(second);
+        }
+
+        split
+    }
+
+    /// Move a start time past every blocked interval the session would overlap
+    fn skip_blocked(mut start: u32, duration: u32, blocked: &[TimeRange]) -> u32 {
+        while let Some(block) = blocked.iter().find(|b| start < b.akhir && b.mulai < start + duration) {
+            start = block.akhir;
+        }
+        start
+    }
+}
+
+// ============================================================================
+// PAUSE CONTROL
+// ============================================================================
+impl PauseControl {
+    pub fn pause(&self) {
+        *self.paused.lock().unwrap() = true;
+    }
+
+    pub fn resume(&self) {
+        *self.paused.lock().unwrap() = false;
+        self.resumed.notify_all();
+    }
+
+    /// Block between iterations while paused; a stop request also ends the wait
+    pub fn wait_while_paused(&self, stop_flag: &AtomicBool) {
+        let mut paused = self.paused.lock().unwrap();
+        while *paused && !stop_flag.load(Ordering::Relaxed) {
+            paused = self.resumed.wait_timeout(paused, Duration::from_millis(200)).unwrap().0;
+        }
+    }
+}
+
+// ============================================================================
+// RUN STATISTICS
+// ============================================================================
+impl RunStatistics {
+    /// Summary of the best fitness of each run, std dev is the sample deviation
+    pub fn new(fitness: &[f64], durations: &[Duration]) -> Self {
+        if fitness.is_empty() {
+            return Self::default();
+        }
+
+        let n = fitness.len() as f64;
+        let mean = fitness.iter().sum::<f64>() / n;
+        let variance = if fitness.len() > 1 {
+            fitness.iter().map(|f| (f - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+
+        let mut sorted = fitness.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let mid = sorted.len() / 2;
+        let median = if sorted.len() % 2 == 0 {
+            (sorted[mid - 1] + sorted[mid]) / 2.0
+        } else {
+            sorted[mid]
+        };
+
+        RunStatistics {
+            mean,
+            std_dev: variance.sqrt(),
+            median,
+            best: sorted[0],
+            worst: sorted[sorted.len() - 1],
+            durations: durations.iter().map(|d| d.as_secs_f64()).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::models::{PenaltyWeights, Room};
+    use crate::algorithms::preference::{parse_csv, parse_preference_csv};
+
+    // The sample data with six rooms in two buildings, so decoding also assigns rooms
+    fn pso(seed: u64) -> PSO {
+        let courses = parse_csv(include_str!("../../data/course.csv"), "Course CSV").unwrap();
+        let prefs = parse_preference_csv(include_str!("../../data/preference.csv")).unwrap();
+        let constraints = ConstraintConfig {
+            ruangan: (1..=6).map(|id| Room { id_ruangan: id, gedung: id % 2 }).collect(),
+            batas_pecah_sks: Some(3),
+            ..ConstraintConfig::default()
+        };
+        PSO::new(courses, prefs, PsoParameters {
+            swarm_size: 10,
+            max_iterations: 15,
+            cognitive_weight: 1.5,
+            social_weight: 1.5,
+            inertia_weight: 0.7,
+            num_runs: Some(2),
+            constraints,
+            penalty_weights: PenaltyWeights::default(),
+            cache_size: None,
+            emit_every: None,
+            checkpoint_every: None,
+            max_velocity: None,
+            mutation_rate: None,
+            seed: Some(seed),
+        })
+    }
+
+    fn assert_same_evaluation(a: Evaluation, b: Evaluation) {
+        assert_eq!(a.hard_violations, b.hard_violations);
+        assert!((a.hard_penalty - b.hard_penalty).abs() < 1e-6, "{:?} != {:?}", a, b);
+        assert!((a.soft_penalty - b.soft_penalty).abs() < 1e-6, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn incremental_evaluation_matches_full_evaluation() {
+        let pso = pso(1);
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut particle = Particle::new(pso.courses.len() * 2, StdRng::seed_from_u64(2));
+
+        for _ in 0..20 {
+            // Moving a few dimensions leaves most groups to come from the cache
+            for _ in 0..5 {
+                let i = rng.random_range(0..particle.position.len());
+                particle.position[i] = rng.random_range(0.0..1.0);
+            }
+
+            let Some(schedule) = PSO::decode_incremental(&mut particle, &pso.courses, &pso.groups, &pso.checker) else {
+                continue;
+            };
+            let full = PSO::position_to_schedule(&particle.position, &pso.courses, &pso.checker.config);
+            assert_eq!(serde_json::to_value(&schedule).unwrap(), serde_json::to_value(&full).unwrap());
+            assert_same_evaluation(PSO::evaluate_decoded(&particle.cache, &pso.checker, &schedule), pso.checker.evaluate(&full));
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_same_schedule() {
+        let best_schedule = |seed: u64| {
+            let mut pso = pso(seed);
+            pso.start(1);
+            for _ in 0..pso.parameters.max_iterations {
+                pso.step();
+            }
+            let schedule = PSO::position_to_schedule(&pso.global_best_position, &pso.courses, &pso.checker.config);
+            (serde_json::to_value(schedule).unwrap(), pso.global_best_fitness)
+        };
+
+        assert_eq!(best_schedule(derive_seed(42, 3)), best_schedule(derive_seed(42, 3)));
+    }
+
+    #[test]
+    fn decoder_leaves_lecturer_break_within_a_class() {
+        let csv = "id,prodi,semester,kode,nama,sks,created_at,id_matkul,id_dosen,create_at,id_waktu,id_kelas,jam_mulai,jam_akhir,id_hari,id_ruangan
+1,1,1,A,Kalkulus,2,,1,7,,1,1,,,,
+2,1,1,B,Fisika,2,,2,7,,1,1,,,,";
+        let courses: Vec<CourseRequest> = parse_csv(csv, "Course CSV").unwrap();
+        let config = ConstraintConfig { jeda_min_dosen: Some(20), ..ConstraintConfig::default() };
+
+        let mut schedule = PSO::position_to_schedule(&[0.1, 0.1, 0.2, 0.2], &courses, &config);
+        schedule.sort_by_key(|c| c.jam_mulai);
+        assert_eq!((schedule[0].hari, schedule[0].jam_mulai, schedule[0].jam_akhir), (1, 480, 560));
+        assert_eq!((schedule[1].hari, schedule[1].jam_mulai, schedule[1].jam_akhir), (1, 580, 660));
+    }
+}
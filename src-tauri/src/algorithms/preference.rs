@@ -0,0 +1,240 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::de::{self, Deserialize, DeserializeOwned, Deserializer, Visitor};
+
+use super::error::ScheduleError;
+use super::models::{
+    AbsenceRequest, AvailabilityRequest, CourseRequest, LecturerPreference, LecturerRequest, PreferenceLevel, TimePreferenceRequest, EVENING_START,
+    SLOTS_PER_DAY, SLOT_MINUTES,
+};
+
+/// Bitmask of the 30-minute slots touched by the interval [mulai, akhir)
+pub fn slot_mask(mulai: u32, akhir: u32) -> u64 {
+    let first = (mulai / SLOT_MINUTES).min(SLOTS_PER_DAY);
+    let last = akhir.div_ceil(SLOT_MINUTES).min(SLOTS_PER_DAY);
+
+    (first..last).fold(0, |mask, slot| mask | (1 << slot))
+}
+
+const ALL_SLOTS: u32 = SLOTS_PER_DAY * SLOT_MINUTES;
+
+impl<'de> Deserialize<'de> for PreferenceLevel {
+    /// Accepts the legacy booleans as well as prefer/neutral/avoid
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct LevelVisitor;
+
+        impl Visitor<'_> for LevelVisitor {
+            type Value = PreferenceLevel;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("true/false or prefer/neutral/avoid")
+            }
+
+            fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+                Ok(if v { PreferenceLevel::Prefer } else { PreferenceLevel::Avoid })
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                match v.trim().to_lowercase().as_str() {
+                    "true" | "prefer" => Ok(PreferenceLevel::Prefer),
+                    "neutral" => Ok(PreferenceLevel::Neutral),
+                    "false" | "avoid" => Ok(PreferenceLevel::Avoid),
+                    _ => Err(E::invalid_value(de::Unexpected::Str(v), &self)),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(LevelVisitor)
+    }
+}
+
+impl LecturerPreference {
+    /// Lecturer with no availability restriction
+    pub fn new(id_dosen: u32) -> Self {
+        Self {
+            id_dosen,
+            prefer: [slot_mask(0, ALL_SLOTS); 7],
+            avoid: [0; 7],
+            back_to_back: None,
+            max_kelas_per_minggu: None,
+            prioritas: 1,
+            max_malam_per_minggu: None,
+            bobot_hari_mengajar: None,
+            absen: vec![],
+        }
+    }
+
+    /// Marks the slots of [mulai, akhir) on the given day with a preference level
+    pub fn set_level(&mut self, hari: u32, mulai: u32, akhir: u32, level: PreferenceLevel) {
+        let Some(i) = hari.checked_sub(1).map(|i| i as usize).filter(|&i| i < 7) else {
+            return;
+        };
+        let mask = slot_mask(mulai, akhir);
+
+        self.prefer[i] &= !mask;
+        self.avoid[i] &= !mask;
+        match level {
+            PreferenceLevel::Prefer => self.prefer[i] |= mask,
+            PreferenceLevel::Avoid => self.avoid[i] |= mask,
+            PreferenceLevel::Neutral => {}
+        }
+    }
+
+    /// Worst preference level over the whole interval on the given day
+    pub fn level(&self, hari: u32, mulai: u32, akhir: u32) -> PreferenceLevel {
+        let Some(i) = hari.checked_sub(1).map(|i| i as usize).filter(|&i| i < 7) else {
+            return PreferenceLevel::Avoid;
+        };
+        let needed = slot_mask(mulai, akhir);
+
+        if needed & self.avoid[i] != 0 {
+            PreferenceLevel::Avoid
+        } else if needed & !self.prefer[i] != 0 {
+            PreferenceLevel::Neutral
+        } else {
+            PreferenceLevel::Prefer
+        }
+    }
+}
+
+impl From<&TimePreferenceRequest> for LecturerPreference {
+    /// Legacy pagi/malam columns: pagi covers the day up to 18:00, malam the rest
+    fn from(p: &TimePreferenceRequest) -> Self {
+        let neutral = PreferenceLevel::Neutral;
+        let days = [
+            (p.senin_pagi, p.senin_malam),
+            (p.selasa_pagi, p.selasa_malam),
+            (p.rabu_pagi, p.rabu_malam),
+            (p.kamis_pagi, p.kamis_malam),
+            (p.jumat_pagi, p.jumat_malam),
+            (p.sabtu_pagi.unwrap_or(neutral), p.sabtu_malam.unwrap_or(neutral)),
+            (p.minggu_pagi.unwrap_or(neutral), p.minggu_malam.unwrap_or(neutral)),
+        ];
+
+        let mut preference = LecturerPreference {
+            back_to_back: p.back_to_back,
+            ..LecturerPreference::new(p.id_dosen)
+        };
+        for (hari, (pagi, malam)) in (1..).zip(days) {
+            preference.set_level(hari, 0, EVENING_START, pagi);
+            preference.set_level(hari, EVENING_START, ALL_SLOTS, malam);
+        }
+
+        preference
+    }
+}
+
+/// Builds preferences from availability rows; slots without rows are avoided
+pub fn from_availability(rows: &[AvailabilityRequest]) -> Vec<LecturerPreference> {
+    let mut preferences: Vec<LecturerPreference> = Vec::new();
+
+    for row in rows {
+        let idx = match preferences.iter().position(|p| p.id_dosen == row.id_dosen) {
+            Some(idx) => idx,
+            None => {
+                preferences.push(LecturerPreference {
+                    prefer: [0; 7],
+                    avoid: [slot_mask(0, ALL_SLOTS); 7],
+                    ..LecturerPreference::new(row.id_dosen)
+                });
+                preferences.len() - 1
+            }
+        };
+
+        let level = row.tingkat.unwrap_or(PreferenceLevel::Prefer);
+        preferences[idx].set_level(row.hari, row.jam_mulai, row.jam_akhir, level);
+    }
+
+    preferences
+}
+
+/// Rows of a CSV, failing with the row and column of the first one that does not parse.
+/// `file` names the input in the error message
+pub fn parse_csv<T: DeserializeOwned>(csv: &str, file: &str) -> Result<Vec<T>, ScheduleError> {
+    let mut rdr = csv::Reader::from_reader(csv.as_bytes());
+    let headers = rdr.headers().map_err(|e| ScheduleError::csv(file, &e, None))?.clone();
+
+    rdr.deserialize()
+        .map(|result| result.map_err(|e| ScheduleError::csv(file, &e, Some(&headers))))
+        .collect()
+}
+
+/// Parses either the legacy pagi/malam CSV or the per-interval availability CSV
+pub fn parse_preference_csv(csv: &str) -> Result<Vec<LecturerPreference>, ScheduleError> {
+    let mut rdr = csv::Reader::from_reader(csv.as_bytes());
+    let headers = rdr
+        .headers()
+        .map_err(|e| ScheduleError::csv("Preference CSV", &e, None))?;
+
+    if headers.iter().any(|h| h == "jam_mulai") {
+        let rows: Vec<AvailabilityRequest> = parse_csv(csv, "Preference CSV")?;
+        Ok(from_availability(&rows))
+    } else {
+        let rows: Vec<TimePreferenceRequest> = parse_csv(csv, "Preference CSV")?;
+        Ok(rows.iter().map(LecturerPreference::from).collect())
+    }
+}
+
+pub fn parse_lecturer_csv(csv: &str) -> Result<Vec<LecturerRequest>, ScheduleError> {
+    parse_csv(csv, "Lecturer CSV")
+}
+
+pub fn parse_absence_csv(csv: &str) -> Result<Vec<AbsenceRequest>, ScheduleError> {
+    parse_csv(csv, "Absence CSV")
+}
+
+/// Courses and lecturer preferences read from files, with the optional lecturer and absence
+/// files merged into the preferences
+pub fn read_input(
+    course_csv: &Path,
+    preference_csv: &Path,
+    lecturer_csv: Option<&Path>,
+    absence_csv: Option<&Path>,
+) -> Result<(Vec<CourseRequest>, Vec<LecturerPreference>), ScheduleError> {
+    let read = |path: &Path| fs::read_to_string(path).map_err(|e| format!("Gagal membaca file {}: {}", path.display(), e));
+
+    let courses = parse_csv(&read(course_csv)?, "Course CSV")?;
+    let mut preferences = parse_preference_csv(&read(preference_csv)?)?;
+    if let Some(path) = lecturer_csv {
+        apply_lecturers(&mut preferences, &parse_lecturer_csv(&read(path)?)?);
+    }
+    if let Some(path) = absence_csv {
+        apply_absences(&mut preferences, &parse_absence_csv(&read(path)?)?);
+    }
+    Ok((courses, preferences))
+}
+
+// Preference entry of a lecturer, added without restrictions if missing
+fn entry(preferences: &mut Vec<LecturerPreference>, id_dosen: u32) -> &mut LecturerPreference {
+    let idx = match preferences.iter().position(|p| p.id_dosen == id_dosen) {
+        Some(idx) => idx,
+        None => {
+            preferences.push(LecturerPreference::new(id_dosen));
+            preferences.len() - 1
+        }
+    };
+
+    &mut preferences[idx]
+}
+
+/// Merges lecturer metadata into the preferences
+pub fn apply_lecturers(preferences: &mut Vec<LecturerPreference>, lecturers: &[LecturerRequest]) {
+    for lecturer in lecturers {
+        let preference = entry(preferences, lecturer.id_dosen);
+        preference.max_kelas_per_minggu = lecturer.max_kelas_per_minggu;
+        preference.prioritas = lecturer.prioritas.unwrap_or(1);
+        preference.max_malam_per_minggu = lecturer.max_malam_per_minggu;
+        preference.bobot_hari_mengajar = lecturer.bobot_hari_mengajar;
+    }
+}
+
+/// Attaches date-specific absences to the lecturers' preferences
+pub fn apply_absences(preferences: &mut Vec<LecturerPreference>, absences: &[AbsenceRequest]) {
+    for absence in absences {
+        entry(preferences, absence.id_dosen)
+            .absen
+            .push((absence.tanggal_mulai, absence.tanggal_akhir));
+    }
+}
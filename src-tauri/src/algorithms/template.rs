@@ -0,0 +1,88 @@
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::models::{CourseRequest, PreferenceLevel, TimePreferenceRequest};
+
+/// Writes course.csv and preference.csv with the headers the parsers expect and a few example
+/// rows. Rows are serialized from the input structs, so the headers cannot drift from them.
+pub fn write_templates(dir: &Path) -> Result<Vec<String>, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Gagal membuat folder {}: {}", dir.display(), e))?;
+
+    let files = [
+        ("course.csv", to_csv(&sample_courses())?),
+        ("preference.csv", to_csv(&sample_preferences())?),
+    ];
+
+    let mut written = Vec::with_capacity(files.len());
+    for (name, csv) in files {
+        let path = dir.join(name);
+        fs::write(&path, csv).map_err(|e| format!("Gagal menulis {}: {}", path.display(), e))?;
+        written.push(path.display().to_string());
+    }
+
+    Ok(written)
+}
+
+fn to_csv<T: Serialize>(rows: &[T]) -> Result<String, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for row in rows {
+        writer.serialize(row).map_err(|e| format!("Template error: {}", e))?;
+    }
+
+    let csv = writer.into_inner().map_err(|e| format!("Template error: {}", e))?;
+    String::from_utf8(csv).map_err(|e| format!("Template error: {}", e))
+}
+
+// Two lecturers, one morning and one evening class, and one course locked to a slot
+fn sample_courses() -> Vec<CourseRequest> {
+    let course = |id_jadwal, id_matkul, id_dosen, id_waktu, id_kelas, sks| CourseRequest {
+        id_jadwal,
+        id_matkul,
+        id_dosen,
+        id_waktu,
+        id_kelas,
+        semester: 1,
+        sks,
+        prodi: 1,
+        fixed_hari: None,
+        fixed_jam_mulai: None,
+        id_ruangan: None,
+        is_online: false,
+        pertemuan: 0,
+    };
+
+    vec![
+        course(1, 101, 1, 1, 1, 3),
+        course(2, 102, 2, 1, 1, 2),
+        course(3, 101, 1, 2, 2, 3),
+        CourseRequest { fixed_hari: Some(1), fixed_jam_mulai: Some(480), ..course(4, 103, 2, 1, 2, 2) },
+        CourseRequest { is_online: true, ..course(5, 104, 1, 2, 1, 2) },
+    ]
+}
+
+fn sample_preferences() -> Vec<TimePreferenceRequest> {
+    use PreferenceLevel::{Avoid, Neutral, Prefer};
+
+    let preference = |id_dosen, pagi, malam| TimePreferenceRequest {
+        id_dosen,
+        senin_pagi: pagi,
+        senin_malam: malam,
+        selasa_pagi: pagi,
+        selasa_malam: malam,
+        rabu_pagi: pagi,
+        rabu_malam: malam,
+        kamis_pagi: pagi,
+        kamis_malam: malam,
+        jumat_pagi: Neutral,
+        jumat_malam: Neutral,
+        sabtu_pagi: None,
+        sabtu_malam: None,
+        minggu_pagi: None,
+        minggu_malam: None,
+        back_to_back: None,
+    };
+
+    vec![preference(1, Prefer, Avoid), preference(2, Neutral, Prefer)]
+}
@@ -1,23 +1,377 @@
 use std::collections::HashMap;
-use std::sync::{atomic::AtomicBool, Arc};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{atomic::{AtomicBool, Ordering}, Arc};
+use std::time::Instant;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
+use crate::algorithms::optimizer::derive_seed;
 use crate::algorithms::models::{
-    CourseRequest, PSO, PsoParameters, TimePreferenceRequest,
+    ConstraintConfig, CourseRequest, LecturerPreference, ParameterSensitivity, PenaltyWeights, PSO, PsoParameters,
+    RunStatistics, TuningProgress,
 };
 
+// Number of runs with the best parameters found, summarized in the result
+const FINAL_RUNS: usize = 5;
+
+// Configurations tried by random search when `samples` is not given
+const DEFAULT_SAMPLES: usize = 30;
+
+// Racing defaults: share of max_iterations for the screening round, and share of the
+// configurations that go on to the full run
+const DEFAULT_RACE_FRACTION: f64 = 0.25;
+const DEFAULT_RACE_KEEP: f64 = 0.3;
+
+// Factor each coordinate descent pass after the first narrows the ranges and grid steps by
+const DEFAULT_SHRINK: f64 = 0.5;
+
+// Most value groups a parameter's trials are split into for the sensitivity analysis
+const MAX_SENSITIVITY_GROUPS: usize = 5;
+
+// Parameters a sampled configuration has a coordinate for, see params_at
+const PARAM_DIMENSIONS: usize = 8;
+
+// Grid searches with more trials than this only run with `confirm_large_grid`
+pub const GRID_CONFIRM_THRESHOLD: usize = 200;
+
+#[derive(Debug, Deserialize, Clone, Default)]
 pub struct ParamRange {
     pub swarm_size: (i32, i32),
     pub max_iterations: (usize, usize),
     pub inertia_weight: (f64, f64),
     pub cognitive_weight: (f64, f64),
     pub social_weight: (f64, f64),
+    #[serde(default)]
+    pub strategy: TuneStrategy,
+    #[serde(default)]
+    pub samples: Option<usize>, // Random search and Latin hypercube only
+    #[serde(default)]
+    pub confirm_large_grid: bool, // Grid search past GRID_CONFIRM_THRESHOLD trials
+    #[serde(default)]
+    pub repeats_per_trial: Option<usize>, // PSO runs averaged per configuration, default 1
+    #[serde(default)]
+    pub parallelism: Option<usize>, // Trials evaluated at the same time, default 1
+    #[serde(default)]
+    pub checkpoint_file: Option<PathBuf>, // JSON file every finished trial is saved to
+    #[serde(default)]
+    pub resume: bool, // Reuse the trials already in checkpoint_file instead of starting over
+    #[serde(default)]
+    pub max_trials: Option<usize>, // Trials before the search ends with the best so far
+    #[serde(default)]
+    pub max_seconds: Option<u64>, // Time after which no new trial is started
+    #[serde(default)]
+    pub racing: bool, // Random/grid search: screen with fewer iterations, run only the best fully
+    #[serde(default)]
+    pub race_fraction: Option<f64>, // Share of max_iterations in the screening round
+    #[serde(default)]
+    pub race_keep: Option<f64>, // Share of configurations that survive the screening
+    #[serde(default)]
+    pub convergence_file: Option<PathBuf>, // CSV with the best fitness per iteration of every trial
+    // Optional extra dimensions, left out of the search when not given. More runs never make
+    // the best fitness worse, so num_runs mostly trades fitness for time.
+    #[serde(default)]
+    pub num_runs: Option<(usize, usize)>,
+    #[serde(default)]
+    pub max_velocity: Option<(f64, f64)>,
+    #[serde(default)]
+    pub mutation_rate: Option<(f64, f64)>,
+    #[serde(default)]
+    pub passes: Option<usize>, // Coordinate descent sweeps, each around the previous best, default 1
+    #[serde(default)]
+    pub shrink: Option<f64>, // Width of a pass's ranges relative to the pass before
+    #[serde(default)]
+    pub seed: Option<u64>, // Master seed; the same seed and range repeat the same trials
+    #[serde(default)]
+    pub steps: HashMap<String, f64>, // Grid step per parameter name, replacing the default one
+    // The objective every trial is scored on, so the parameters are tuned for the schedule
+    // that will actually be optimized
+    #[serde(default)]
+    pub constraints: ConstraintConfig,
+    #[serde(default)]
+    pub penalty_weights: PenaltyWeights,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TuneStrategy {
+    // One parameter at a time over a fixed grid, keeping the best value of each
+    #[default]
+    CoordinateDescent,
+    // Whole configurations drawn uniformly from the range, which also finds settings
+    // where parameters only work well together
+    RandomSearch,
+    // Like random search, but each parameter's range is split into `samples` equal parts that
+    // are all used once, so a few samples already cover every range evenly
+    LatinHypercube,
+    // Every combination of the grid values; exact but grows multiplicatively, so only for
+    // small ranges
+    Grid,
+}
+
+impl ParamRange {
+    /// Configurations the tuning will try before the final runs
+    pub fn estimated_trials(&self) -> usize {
+        let steps = grid_steps(self, 1.0);
+        match self.strategy {
+            // Later passes keep about as many values per parameter as the first
+            TuneStrategy::CoordinateDescent => {
+                steps.iter().map(|(_, values)| values.len()).sum::<usize>().saturating_mul(self.passes())
+            }
+            TuneStrategy::RandomSearch | TuneStrategy::LatinHypercube => self.samples.unwrap_or(DEFAULT_SAMPLES).max(1),
+            TuneStrategy::Grid => steps.iter().fold(1, |total: usize, (_, values)| total.saturating_mul(values.len())),
+        }
+    }
+
+    pub fn repeats(&self) -> usize {
+        self.repeats_per_trial.unwrap_or(1).max(1)
+    }
+
+    pub fn parallelism(&self) -> usize {
+        self.parallelism.unwrap_or(1).max(1)
+    }
+
+    fn passes(&self) -> usize {
+        self.passes.unwrap_or(1).max(1)
+    }
+
+    fn shrink(&self) -> f64 {
+        self.shrink.unwrap_or(DEFAULT_SHRINK).clamp(0.05, 1.0)
+    }
+
+    fn race_fraction(&self) -> f64 {
+        self.race_fraction.unwrap_or(DEFAULT_RACE_FRACTION).clamp(0.01, 1.0)
+    }
+
+    fn race_keep(&self) -> f64 {
+        self.race_keep.unwrap_or(DEFAULT_RACE_KEEP).clamp(0.0, 1.0)
+    }
+
+    /// Refuses a grid search of more than GRID_CONFIRM_THRESHOLD PSO runs unless it was
+    /// confirmed; returns the number of runs. With racing a run counts as the share of a full
+    /// run it costs.
+    pub fn check_trial_count(&self) -> Result<usize, String> {
+        let mut trials = self.estimated_trials().min(self.max_trials.unwrap_or(usize::MAX));
+        if self.racing && self.strategy != TuneStrategy::CoordinateDescent {
+            trials = (trials as f64 * (self.race_fraction() + self.race_keep())).ceil() as usize;
+        }
+        let runs = trials.saturating_mul(self.repeats());
+        if self.strategy == TuneStrategy::Grid && runs > GRID_CONFIRM_THRESHOLD && !self.confirm_large_grid {
+            return Err(format!(
+                "Grid search akan menjalankan {} run PSO (batas {}); persempit range atau set confirm_large_grid",
+                runs, GRID_CONFIRM_THRESHOLD
+            ));
+        }
+        Ok(runs)
+    }
+}
+
+/// One course list with its lecturer preferences. Tuning on several of them scores a
+/// configuration by its mean fitness over all, so it does not fit one department only.
+#[derive(Debug, Clone)]
+pub struct Dataset {
+    pub courses: Vec<CourseRequest>,
+    pub time_preferences: Vec<LecturerPreference>,
+}
+
+/// A finished trial as a tuner sees it
+#[derive(Debug, Clone)]
+pub struct TrialResult {
+    pub params: PsoParameters,
+    pub statistics: RunStatistics,
+    // Progress name and value it was reported with, see Tuner::describe
+    pub parameter: String,
+    pub value: f64,
+}
+
+/// A search strategy. A tuner only proposes configurations, a batch at a time, from the
+/// trials so far; running them in parallel, the trial log, budgets, racing, progress, the
+/// final runs and the export are the same for every strategy.
+pub trait Tuner: Send {
+    /// Next batch of configurations to try, given every finished trial batch by batch in the
+    /// order proposed. Empty when the search is done.
+    fn next_candidates(&mut self, history: &[TrialResult]) -> Vec<PsoParameters>;
+
+    /// Progress name and value of the `index`-th configuration of the last batch
+    fn describe(&self, index: usize) -> (String, f64);
+
+    /// Trials the whole search is expected to take; may change from batch to batch
+    fn total_trials(&self) -> usize;
+
+    /// Parameters for the final runs, the best trial unless the strategy knows better
+    fn best(&self, history: &[TrialResult]) -> Option<PsoParameters> {
+        best_trial(history).map(|trial| trial.params.clone())
+    }
+
+    /// The "experiments" of the result, by default every configuration with its fitness
+    fn report(&self, history: &[TrialResult]) -> Value {
+        history
+            .iter()
+            .map(|trial| {
+                json!({ "params": trial.params, "fitness": trial.statistics.mean, "std_dev": trial.statistics.std_dev })
+            })
+            .collect()
+    }
+}
+
+// The trial with the lowest mean fitness, the earliest one on a tie
+fn best_trial(trials: &[TrialResult]) -> Option<&TrialResult> {
+    trials.iter().fold(None, |best, trial| {
+        if beats(&trial.statistics, best.map(|best: &TrialResult| &best.statistics)) { Some(trial) } else { best }
+    })
+}
+
+// One parameter at a time over its grid values, keeping the best value of each. With several
+// passes the ranges and steps are narrowed around the best parameters after every pass.
+struct CoordinateDescent {
+    range: ParamRange,
+    pass_range: ParamRange,
+    pass: usize,
+    scale: f64,
+    steps: std::vec::IntoIter<(&'static str, Vec<f64>)>,
+    current: Option<(&'static str, Vec<f64>)>,
+    best_params: PsoParameters,
+    // Trials in the history before the current step
+    seen: usize,
+    total: usize,
+}
+
+impl CoordinateDescent {
+    fn new(range: &ParamRange) -> Self {
+        let steps = grid_steps(range, 1.0);
+        let total = steps.iter().map(|(_, values)| values.len()).sum::<usize>() * range.passes();
+        Self {
+            range: range.clone(),
+            pass_range: range.clone(),
+            pass: 1,
+            scale: 1.0,
+            steps: steps.into_iter(),
+            current: None,
+            best_params: lower_bound(range),
+            seen: 0,
+            total,
+        }
+    }
+
+    // Best trial of the current step, compared in grid order whatever order they finished in
+    fn step_best<'a>(&self, history: &'a [TrialResult]) -> Option<&'a TrialResult> {
+        best_trial(history.get(self.seen..).unwrap_or_default())
+    }
+}
+
+impl Tuner for CoordinateDescent {
+    fn next_candidates(&mut self, history: &[TrialResult]) -> Vec<PsoParameters> {
+        if let Some((name, _)) = self.current {
+            // A step the budget cut off before any trial keeps the value it had
+            let best = self.step_best(history).map(|trial| (trial.value, trial.statistics.mean));
+            if let Some((value, _)) = best {
+                set_param(&mut self.best_params, name, value);
+            }
+            println!(
+                "✅ Parameter {} terbaik: {} dengan fitness {:.4}",
+                name,
+                get_param(&self.best_params, name).unwrap_or_default(),
+                best.map_or(f64::INFINITY, |(_, fitness)| fitness)
+            );
+        }
+        self.seen = history.len();
+
+        let (name, values) = loop {
+            if let Some(step) = self.steps.find(|(_, values)| !values.is_empty()) {
+                break step;
+            }
+            if self.pass >= self.range.passes() {
+                self.current = None;
+                return Vec::new();
+            }
+            self.pass += 1;
+            self.scale *= self.range.shrink();
+            self.pass_range = narrow_range(&self.range, &self.pass_range, &self.best_params, self.range.shrink());
+            let steps = grid_steps(&self.pass_range, self.scale);
+            // The narrowed grids can be a value shorter or longer, so the total is re-estimated
+            let pass_trials: usize = steps.iter().map(|(_, values)| values.len()).sum();
+            self.total = history.len() + pass_trials * (self.range.passes() - self.pass + 1);
+            self.steps = steps.into_iter();
+            println!("\n🔁 Putaran {}/{}: range dipersempit di sekitar parameter terbaik", self.pass, self.range.passes());
+        };
+        println!("\n🔧 Menyesuaikan parameter: {}", name);
+
+        let candidates = values
+            .iter()
+            .map(|value| {
+                let mut params = self.best_params.clone();
+                set_param(&mut params, name, *value);
+                params
+            })
+            .collect();
+        self.current = Some((name, values));
+        candidates
+    }
+
+    fn describe(&self, index: usize) -> (String, f64) {
+        self.current.as_ref().map_or((String::new(), 0.0), |(name, values)| (name.to_string(), values[index]))
+    }
+
+    fn total_trials(&self) -> usize {
+        self.total
+    }
+
+    fn best(&self, history: &[TrialResult]) -> Option<PsoParameters> {
+        let mut params = self.best_params.clone();
+        if let (Some((name, _)), Some(trial)) = (&self.current, self.step_best(history)) {
+            set_param(&mut params, name, trial.value);
+        }
+        Some(params)
+    }
+
+    // (value, mean fitness, std dev) of every trial, per parameter
+    fn report(&self, history: &[TrialResult]) -> Value {
+        let mut report: HashMap<&str, Vec<(f64, f64, f64)>> = HashMap::new();
+        for trial in history {
+            report.entry(&trial.parameter).or_default().push((trial.value, trial.statistics.mean, trial.statistics.std_dev));
+        }
+        json!(report)
+    }
+}
+
+// Random, Latin hypercube and grid search: every configuration is known up front and goes out
+// as one batch. Progress reports the configuration number as the value, since every parameter
+// changes at once.
+struct Configurations {
+    label: &'static str,
+    pending: Vec<PsoParameters>,
+    total: usize,
+}
+
+impl Configurations {
+    fn new(label: &'static str, configurations: Vec<PsoParameters>) -> Self {
+        Self { label, total: configurations.len(), pending: configurations }
+    }
+}
+
+impl Tuner for Configurations {
+    fn next_candidates(&mut self, _history: &[TrialResult]) -> Vec<PsoParameters> {
+        if !self.pending.is_empty() {
+            println!("\n🎲 {} dengan {} konfigurasi", self.label, self.total);
+        }
+        std::mem::take(&mut self.pending)
+    }
+
+    fn describe(&self, index: usize) -> (String, f64) {
+        (self.label.to_string(), (index + 1) as f64)
+    }
+
+    fn total_trials(&self) -> usize {
+        self.total
+    }
 }
 
 pub async fn optimize_by_range(
-    courses: &[CourseRequest],
-    time_preferences: &[TimePreferenceRequest],
+    datasets: Vec<Dataset>,
     param_range: ParamRange,
+    stop_flag: Arc<AtomicBool>,
+    on_trial: impl Fn(&TuningProgress),
 ) -> (Value, HashMap<String, Vec<(PsoParameters, f64)>>) {
     println!("📊 Memulai optimasi PSO dengan parameter range:");
     println!("- swarm_size       : {:?}", param_range.swarm_size);
@@ -25,20 +379,408 @@ pub async fn optimize_by_range(
     println!("- inertia_weight   : {:?}", param_range.inertia_weight);
     println!("- cognitive_weight : {:?}", param_range.cognitive_weight);
     println!("- social_weight    : {:?}", param_range.social_weight);
+    println!("- dataset          : {}", datasets.len());
+    if let Some(seed) = param_range.seed {
+        println!("- seed             : {}", seed);
+    }
 
-    let mut full_experiments: HashMap<String, Vec<(PsoParameters, f64)>> = HashMap::new();
+    let log = TrialLog::open(param_range.checkpoint_file.clone(), param_range.resume);
 
-    let mut best_params = PsoParameters {
-        swarm_size: param_range.swarm_size.0,
-        max_iterations: param_range.max_iterations.0,
-        inertia_weight: param_range.inertia_weight.0,
-        cognitive_weight: param_range.cognitive_weight.0,
-        social_weight: param_range.social_weight.0,
-        num_runs: Some(1),
+    let samples = param_range.estimated_trials();
+    // Samples drawn before the interruption come first, so resuming draws only the rest
+    let logged = log.trials.iter().map(|record| record.params.clone()).take(samples);
+    let mut rng = match param_range.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_rng(&mut rand::rng()),
     };
+    let mut tuner: Box<dyn Tuner> = match param_range.strategy {
+        TuneStrategy::CoordinateDescent => Box::new(CoordinateDescent::new(&param_range)),
+        TuneStrategy::RandomSearch => Box::new(Configurations::new(
+            "random_search",
+            logged.chain(std::iter::repeat_with(|| sample_params(&param_range, &mut rng))).take(samples).collect(),
+        )),
+        TuneStrategy::LatinHypercube => Box::new(Configurations::new(
+            "latin_hypercube",
+            logged.chain(latin_hypercube(&param_range, samples, &mut rng)).take(samples).collect(),
+        )),
+        TuneStrategy::Grid => Box::new(Configurations::new("grid", grid_configurations(&param_range))),
+    };
+
+    run_tuner(tuner.as_mut(), datasets, &param_range, log, stop_flag, on_trial).await
+}
+
+/// Tunes with a strategy of the caller's own; `param_range` still sets the trial log, budgets,
+/// racing, repeats and parallelism, and the ranges only matter as far as `tuner` uses them.
+pub async fn optimize_with_tuner(
+    tuner: &mut dyn Tuner,
+    datasets: Vec<Dataset>,
+    param_range: &ParamRange,
+    stop_flag: Arc<AtomicBool>,
+    on_trial: impl Fn(&TuningProgress),
+) -> (Value, HashMap<String, Vec<(PsoParameters, f64)>>) {
+    let log = TrialLog::open(param_range.checkpoint_file.clone(), param_range.resume);
+    run_tuner(tuner, datasets, param_range, log, stop_flag, on_trial).await
+}
+
+// Evaluates the tuner's batches until it runs out or the search has to end, then runs the
+// best parameters. The result and experiments keep the shape they had per strategy.
+async fn run_tuner(
+    tuner: &mut dyn Tuner,
+    datasets: Vec<Dataset>,
+    param_range: &ParamRange,
+    mut log: TrialLog,
+    stop_flag: Arc<AtomicBool>,
+    on_trial: impl Fn(&TuningProgress),
+) -> (Value, HashMap<String, Vec<(PsoParameters, f64)>>) {
+    let datasets = Arc::new(datasets);
+    let mut limits = SearchLimits::new(param_range, stop_flag.clone());
+    let racing = param_range.racing && param_range.strategy != TuneStrategy::CoordinateDescent;
+
+    let mut history: Vec<TrialResult> = Vec::new();
+    let mut screening = Vec::new();
+    // Configurations racing dropped, which the total no longer counts
+    let mut raced_out = 0;
+    let mut fallback = None;
+    let mut overall_best = f64::INFINITY;
+    let started = Instant::now();
+
+    loop {
+        let candidates: Vec<PsoParameters> = tuner
+            .next_candidates(&history)
+            .into_iter()
+            .map(|params| with_trial_seed(params, param_range.seed))
+            .collect();
+        if candidates.is_empty() {
+            break;
+        }
+
+        // Positions in the batch, so the tuner's descriptions still apply after racing
+        let order: Vec<usize> = if racing {
+            let (survivors, report) = race(&candidates, &datasets, param_range, &mut limits, &mut log, &on_trial).await;
+            raced_out += candidates.len() - survivors.len();
+            screening.extend(report);
+            survivors
+        } else {
+            (0..candidates.len()).collect()
+        };
+        // Stopped before the first trial finished: fall back to the first configuration, which
+        // after racing is the best screened one
+        fallback = fallback.or_else(|| order.first().map(|&index| candidates[index].clone()));
+
+        let configurations: Vec<PsoParameters> = order.iter().map(|&index| candidates[index].clone()).collect();
+        let described: Vec<(String, f64)> = order.iter().map(|&index| tuner.describe(index)).collect();
+        let total_trials = tuner.total_trials().saturating_sub(raced_out);
+        let mut trial = history.len();
+        let mut results: Vec<Option<RunStatistics>> = vec![None; configurations.len()];
+
+        evaluate_all(&configurations, &datasets, param_range, &mut limits, &mut log, |index, trial_stats| {
+            // An interrupted trial did not finish, so its fitness is not comparable
+            if stop_flag.load(Ordering::Relaxed) {
+                return;
+            }
+            trial += 1;
+            overall_best = overall_best.min(trial_stats.mean);
+            let (parameter, value) = &described[index];
+            println!("🧪 [{}/{}] {} = {} selesai", trial, total_trials, parameter, value);
+            on_trial(&TuningProgress {
+                parameter: parameter.clone(),
+                trial,
+                total_trials,
+                value: *value,
+                fitness: trial_stats.mean,
+                std_dev: trial_stats.std_dev,
+                best_fitness: overall_best,
+                elapsed_seconds: started.elapsed().as_secs_f64(),
+                eta_seconds: eta_seconds(started, trial, total_trials),
+            });
+            results[index] = Some(trial_stats);
+        })
+        .await;
+
+        history.extend(configurations.into_iter().zip(described).zip(results).filter_map(
+            |((params, (parameter, value)), statistics)| {
+                Some(TrialResult { params, statistics: statistics?, parameter, value })
+            },
+        ));
+
+        if stop_flag.load(Ordering::Relaxed) {
+            println!("⏹️  Tuning dihentikan.");
+            break;
+        }
+        if limits.exhausted() && history.len() < tuner.total_trials().saturating_sub(raced_out) {
+            println!("⏱️  Batas trial/waktu tuning tercapai.");
+            break;
+        }
+    }
+
+    export_convergence(param_range, &log);
+
+    let total_trials = tuner.total_trials().saturating_sub(raced_out);
+    let best_params = tuner.best(&history).or(fallback).unwrap_or_else(|| lower_bound(param_range));
+    let best_params = with_trial_seed(best_params, param_range.seed);
+    println!("✅ Konfigurasi terbaik dengan fitness {:.4}", overall_best);
+
+    // Cut short by max_trials/max_seconds rather than by a stop request
+    let budget_exhausted = history.len() < total_trials && !limits.stopped();
+    let mut result = final_runs(&datasets, best_params, overall_best, tuner.report(&history), stop_flag).await;
+    result["budget_exhausted"] = json!(budget_exhausted);
+    if racing {
+        result["racing"] = json!(screening);
+    }
+
+    let mut experiments: HashMap<String, Vec<(PsoParameters, f64)>> = HashMap::new();
+    for trial in history {
+        experiments.entry(trial.parameter).or_default().push((trial.params, trial.statistics.mean));
+    }
+    result["sensitivity"] = json!(sensitivity(experiments.values().flatten()));
+    (result, experiments)
+}
+
+// Screening round of racing: every configuration runs with a fraction of its iterations and
+// only the best share goes on to the full run. Returns the positions of the survivors, best
+// first, and a report of the screening.
+async fn race(
+    configurations: &[PsoParameters],
+    datasets: &Arc<Vec<Dataset>>,
+    param_range: &ParamRange,
+    limits: &mut SearchLimits,
+    log: &mut TrialLog,
+    on_trial: &impl Fn(&TuningProgress),
+) -> (Vec<usize>, Vec<Value>) {
+    let fraction = param_range.race_fraction();
+    let screening: Vec<PsoParameters> = configurations
+        .iter()
+        .map(|params| PsoParameters {
+            max_iterations: ((params.max_iterations as f64 * fraction).ceil() as usize).max(1),
+            ..params.clone()
+        })
+        .collect();
+    let total_trials = screening.len();
+    println!("\n🏁 Racing: {} konfigurasi dengan {:.0}% iterasi", total_trials, fraction * 100.0);
+
+    let stop_flag = limits.stop_flag.clone();
+    let mut results: Vec<Option<RunStatistics>> = vec![None; total_trials];
+    let mut trial = 0;
+    let mut best_fitness = f64::INFINITY;
+    let started = Instant::now();
+
+    evaluate_all(&screening, datasets, param_range, limits, log, |index, trial_stats| {
+        if stop_flag.load(Ordering::Relaxed) {
+            return;
+        }
+        trial += 1;
+        best_fitness = best_fitness.min(trial_stats.mean);
+        on_trial(&TuningProgress {
+            parameter: "racing".to_string(),
+            trial,
+            total_trials,
+            value: (index + 1) as f64,
+            fitness: trial_stats.mean,
+            std_dev: trial_stats.std_dev,
+            best_fitness,
+            elapsed_seconds: started.elapsed().as_secs_f64(),
+            eta_seconds: eta_seconds(started, trial, total_trials),
+        });
+        results[index] = Some(trial_stats);
+    })
+    .await;
 
-    let mut history: HashMap<String, Vec<(f64, f64)>> = HashMap::new();
+    // Ranked like the full trials; configurations the budget never reached are dropped
+    let mut ranked: Vec<(usize, RunStatistics)> =
+        results.into_iter().enumerate().filter_map(|(index, stats)| Some((index, stats?))).collect();
+    ranked.sort_by(|(_, a), (_, b)| {
+        (a.mean, a.std_dev).partial_cmp(&(b.mean, b.std_dev)).unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let survivors = ((ranked.len() as f64 * param_range.race_keep()).ceil() as usize).clamp(1, ranked.len().max(1));
+    println!("✂️  {} dari {} konfigurasi lanjut ke putaran penuh", survivors.min(ranked.len()), total_trials);
+
+    let report = ranked
+        .iter()
+        .enumerate()
+        .map(|(rank, (index, stats))| {
+            json!({
+                "params": configurations[*index],
+                "fitness": stats.mean,
+                "std_dev": stats.std_dev,
+                "survived": rank < survivors,
+            })
+        })
+        .collect();
+    let survivors = ranked.iter().take(survivors).map(|(index, _)| *index).collect();
+
+    (survivors, report)
+}
+
+// Runs every configuration, up to `parallelism` at a time on the blocking pool, and hands
+// each result to `on_done` with its index as soon as it finishes, so not in order.
+// Configurations already in the log are answered from it without running. Once the limits
+// are reached no new trial starts, but the running ones finish.
+async fn evaluate_all(
+    configurations: &[PsoParameters],
+    datasets: &Arc<Vec<Dataset>>,
+    param_range: &ParamRange,
+    limits: &mut SearchLimits,
+    log: &mut TrialLog,
+    mut on_done: impl FnMut(usize, RunStatistics),
+) {
+    let repeats = param_range.repeats();
+    let mut pending = configurations.iter().cloned().enumerate().peekable();
+    let mut running = tokio::task::JoinSet::new();
+
+    loop {
+        while running.len() < param_range.parallelism() && pending.peek().is_some() && limits.take_trial() {
+            let Some((index, params)) = pending.next() else { break };
+            if let Some(trial_stats) = log.find(&params) {
+                on_done(index, trial_stats);
+                continue;
+            }
+
+            let (datasets, stop_flag) = (datasets.clone(), limits.stop_flag.clone());
+            let runtime = tokio::runtime::Handle::current();
+            running.spawn_blocking(move || {
+                let (trial_stats, convergence) = runtime.block_on(run_fitness(&params, &datasets, repeats, stop_flag));
+                (index, params, trial_stats, convergence)
+            });
+        }
+
+        match running.join_next().await {
+            Some(Ok((index, params, trial_stats, convergence))) => {
+                // A trial cut short by a stop is not worth resuming from
+                if !limits.stopped() {
+                    log.record(params, &trial_stats, convergence);
+                }
+                on_done(index, trial_stats);
+            }
+            Some(Err(e)) => eprintln!("⚠️  Trial tuning gagal: {}", e),
+            None => break,
+        }
+    }
+}
+
+// When a search has to end early: a stop request, or its trial or time budget running out
+struct SearchLimits {
+    stop_flag: Arc<AtomicBool>,
+    max_trials: Option<usize>,
+    deadline: Option<Instant>,
+    trials: usize, // Started so far, including those taken from the checkpoint
+}
+
+impl SearchLimits {
+    fn new(range: &ParamRange, stop_flag: Arc<AtomicBool>) -> Self {
+        Self {
+            stop_flag,
+            max_trials: range.max_trials,
+            deadline: range.max_seconds.map(|seconds| Instant::now() + std::time::Duration::from_secs(seconds)),
+            trials: 0,
+        }
+    }
+
+    fn stopped(&self) -> bool {
+        self.stop_flag.load(Ordering::Relaxed)
+    }
+
+    fn exhausted(&self) -> bool {
+        self.max_trials.is_some_and(|max| self.trials >= max) || self.deadline.is_some_and(|at| Instant::now() >= at)
+    }
 
+    // Counts a trial about to start, or refuses it
+    fn take_trial(&mut self) -> bool {
+        if self.stopped() || self.exhausted() {
+            return false;
+        }
+        self.trials += 1;
+        true
+    }
+}
+
+// Finished trials of a search, written to `checkpoint_file` after each one so a search cut
+// short by a reboot can pick up where it was. The file is kept after the search ends.
+#[derive(Default)]
+struct TrialLog {
+    path: Option<PathBuf>,
+    trials: Vec<TrialRecord>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TrialRecord {
+    params: PsoParameters,
+    statistics: RunStatistics,
+    #[serde(default)]
+    convergence: Vec<Vec<f64>>, // Best fitness per iteration, one curve per repeat
+}
+
+impl TrialLog {
+    fn open(path: Option<PathBuf>, resume: bool) -> Self {
+        let trials = match path.as_ref().filter(|_| resume) {
+            Some(path) => match fs::read_to_string(path) {
+                Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                    eprintln!("⚠️  Checkpoint tuning {} rusak, mulai dari awal: {}", path.display(), e);
+                    Vec::new()
+                }),
+                Err(_) => {
+                    println!("ℹ️  Checkpoint tuning {} belum ada, mulai dari awal", path.display());
+                    Vec::new()
+                }
+            },
+            None => Vec::new(),
+        };
+
+        if !trials.is_empty() {
+            println!("🔁 {} trial dilanjutkan dari checkpoint", trials.len());
+        }
+        Self { path, trials }
+    }
+
+    // Only the tuned parameters differ between trials of one search
+    fn find(&self, params: &PsoParameters) -> Option<RunStatistics> {
+        self.trials
+            .iter()
+            .find(|record| {
+                let logged = &record.params;
+                logged.swarm_size == params.swarm_size
+                    && logged.max_iterations == params.max_iterations
+                    && logged.inertia_weight == params.inertia_weight
+                    && logged.cognitive_weight == params.cognitive_weight
+                    && logged.social_weight == params.social_weight
+                    && logged.num_runs == params.num_runs
+                    && logged.max_velocity == params.max_velocity
+                    && logged.mutation_rate == params.mutation_rate
+                    && logged.seed == params.seed
+            })
+            .map(|record| record.statistics.clone())
+    }
+
+    fn record(&mut self, params: PsoParameters, statistics: &RunStatistics, convergence: Vec<Vec<f64>>) {
+        self.trials.push(TrialRecord { params, statistics: statistics.clone(), convergence });
+        let Some(path) = &self.path else { return };
+
+        // Written next to the target first, so a crash mid-write keeps the previous file
+        let tmp = path.with_extension("tmp");
+        let saved = serde_json::to_string(&self.trials)
+            .map_err(|e| e.to_string())
+            .and_then(|json| fs::write(&tmp, json).map_err(|e| e.to_string()))
+            .and_then(|_| fs::rename(&tmp, path).map_err(|e| e.to_string()));
+        if let Err(e) = saved {
+            eprintln!("⚠️  Gagal menyimpan checkpoint tuning: {}", e);
+        }
+    }
+}
+
+// Remaining time at the average pace so far. Trials answered from a checkpoint count as
+// instant, so right after resuming the estimate is optimistic.
+fn eta_seconds(started: Instant, done: usize, total: usize) -> Option<f64> {
+    (done > 0).then(|| started.elapsed().as_secs_f64() / done as f64 * total.saturating_sub(done) as f64)
+}
+
+// Lower mean fitness wins; on equal means (e.g. several feasible configurations) the
+// steadier one does
+fn beats(candidate: &RunStatistics, best: Option<&RunStatistics>) -> bool {
+    best.is_none_or(|best| (candidate.mean, candidate.std_dev) < (best.mean, best.std_dev))
+}
+
+// Grid values of every parameter; `scale` shrinks the steps along with the ranges of later
+// coordinate descent passes
+fn grid_steps(range: &ParamRange, scale: f64) -> Vec<(&'static str, Vec<f64>)> {
     fn range_float(start: f64, end: f64, step: f64) -> Vec<f64> {
         let mut result = Vec::new();
         let mut current = start;
@@ -53,119 +795,499 @@ pub async fn optimize_by_range(
         (start..=end).step_by(step as usize).map(|v| v as f64).collect()
     }
 
-    async fn run_fitness(
-        params: &PsoParameters,
-        courses: &[CourseRequest],
-        prefs: &[TimePreferenceRequest],
-    ) -> f64 {
-        println!(
-            "⚙️  Menjalankan fitness dengan params: swarm={}, iter={}, iw={:.2}, cw={:.2}, sw={:.2}",
-            params.swarm_size,
-            params.max_iterations,
-            params.inertia_weight,
-            params.cognitive_weight,
-            params.social_weight
-        );
-
-        let mut pso = PSO::new(courses.to_vec(), prefs.to_vec(), params.clone());
-        let (_, fitness) = pso
-            .optimize(None, None, &mut vec![], Arc::new(AtomicBool::new(false)))
-            .await;
-
-        println!("📈 Fitness: {:.4}", fitness);
-        fitness
-    }
+    let step = |name: &str, default: f64| range.steps.get(name).copied().filter(|step| *step > 0.0).unwrap_or(default);
+    let float_step = |name: &str, default: f64| step(name, default) * scale;
+    let int_step = |name: &str, default: f64| ((step(name, default) * scale).round() as i32).max(1);
 
-    let steps = vec![
-        ("swarm_size", range_int(param_range.swarm_size.0, param_range.swarm_size.1, 100)),
+    let mut steps = vec![
+        ("swarm_size", range_int(range.swarm_size.0, range.swarm_size.1, int_step("swarm_size", 100.0))),
         (
             "max_iterations",
-            range_int(
-                param_range.max_iterations.0 as i32,
-                param_range.max_iterations.1 as i32,
-                100,
-            ),
+            range_int(range.max_iterations.0 as i32, range.max_iterations.1 as i32, int_step("max_iterations", 100.0)),
         ),
         (
             "inertia_weight",
-            range_float(param_range.inertia_weight.0, param_range.inertia_weight.1, 0.1),
+            range_float(range.inertia_weight.0, range.inertia_weight.1, float_step("inertia_weight", 0.1)),
         ),
         (
             "cognitive_weight",
-            range_float(param_range.cognitive_weight.0, param_range.cognitive_weight.1, 0.1),
-        ),
-        (
-            "social_weight",
-            range_float(param_range.social_weight.0, param_range.social_weight.1, 0.1),
+            range_float(range.cognitive_weight.0, range.cognitive_weight.1, float_step("cognitive_weight", 0.1)),
         ),
+        ("social_weight", range_float(range.social_weight.0, range.social_weight.1, float_step("social_weight", 0.1))),
     ];
+    if let Some((low, high)) = range.num_runs {
+        steps.push(("num_runs", range_int(low as i32, high as i32, int_step("num_runs", 1.0))));
+    }
+    if let Some((low, high)) = range.max_velocity {
+        steps.push(("max_velocity", range_float(low, high, float_step("max_velocity", 0.1))));
+    }
+    if let Some((low, high)) = range.mutation_rate {
+        steps.push(("mutation_rate", range_float(low, high, float_step("mutation_rate", 0.01))));
+    }
+    steps
+}
 
-    for (param_name, values) in steps {
-        println!("\n🔧 Menyesuaikan parameter: {}", param_name);
+// Ranges of the next coordinate descent pass: `shrink` times as wide as the current ones and
+// centred on the best parameters, shifted where needed to stay inside the original range
+fn narrow_range(original: &ParamRange, current: &ParamRange, best: &PsoParameters, shrink: f64) -> ParamRange {
+    let around = |center: f64, (low, high): (f64, f64), (min, max): (f64, f64)| {
+        let width = ((high - low) * shrink).min(max - min);
+        let low = (center - width / 2.0).clamp(min, max - width);
+        // Rounded so the grid values stay readable
+        ((low * 1e4).round() / 1e4, ((low + width) * 1e4).round() / 1e4)
+    };
+    let around_int = |center: f64, (low, high): (f64, f64), bounds: (f64, f64)| {
+        let (low, high) = around(center, (low, high), bounds);
+        (low.round(), high.round())
+    };
+    let as_f64 = |(low, high): (usize, usize)| (low as f64, high as f64);
+    let to_usize = |(low, high): (f64, f64)| (low as usize, high as usize);
+
+    let swarm_size = around_int(
+        best.swarm_size as f64,
+        (current.swarm_size.0 as f64, current.swarm_size.1 as f64),
+        (original.swarm_size.0 as f64, original.swarm_size.1 as f64),
+    );
+    ParamRange {
+        swarm_size: (swarm_size.0 as i32, swarm_size.1 as i32),
+        max_iterations: to_usize(around_int(
+            best.max_iterations as f64,
+            as_f64(current.max_iterations),
+            as_f64(original.max_iterations),
+        )),
+        inertia_weight: around(best.inertia_weight, current.inertia_weight, original.inertia_weight),
+        cognitive_weight: around(best.cognitive_weight, current.cognitive_weight, original.cognitive_weight),
+        social_weight: around(best.social_weight, current.social_weight, original.social_weight),
+        num_runs: current.num_runs.zip(original.num_runs).map(|(range, bounds)| {
+            to_usize(around_int(best.num_runs.unwrap_or(1) as f64, as_f64(range), as_f64(bounds)))
+        }),
+        max_velocity: current.max_velocity.zip(original.max_velocity).map(|(range, bounds)| {
+            around(best.max_velocity.unwrap_or(bounds.0), range, bounds)
+        }),
+        mutation_rate: current.mutation_rate.zip(original.mutation_rate).map(|(range, bounds)| {
+            around(best.mutation_rate.unwrap_or(bounds.0), range, bounds)
+        }),
+        ..current.clone()
+    }
+}
 
-        let mut best_val = values[0];
-        let mut best_fitness = f64::INFINITY;
-        let mut records = vec![];
+fn get_param(params: &PsoParameters, name: &str) -> Option<f64> {
+    match name {
+        "swarm_size" => Some(params.swarm_size as f64),
+        "max_iterations" => Some(params.max_iterations as f64),
+        "inertia_weight" => Some(params.inertia_weight),
+        "cognitive_weight" => Some(params.cognitive_weight),
+        "social_weight" => Some(params.social_weight),
+        "num_runs" => params.num_runs.map(|runs| runs as f64),
+        "max_velocity" => params.max_velocity,
+        "mutation_rate" => params.mutation_rate,
+        _ => None,
+    }
+}
 
-        for (i, val) in values.iter().enumerate() {
-            println!("🧪 [{}/{}] Menguji {} = {}", i + 1, values.len(), param_name, val);
+/// How much of the fitness variance over the trials each parameter explains on its own,
+/// most important first: the trials are grouped by the parameter's value and the variance of
+/// the group means is compared with the total (eta squared, a first-order fANOVA). Effects
+/// that only show up when parameters interact are not separated out. Parameters that never
+/// changed are left out.
+pub fn sensitivity<'a>(trials: impl IntoIterator<Item = &'a (PsoParameters, f64)>) -> Vec<ParameterSensitivity> {
+    let trials: Vec<&(PsoParameters, f64)> = trials.into_iter().filter(|(_, fitness)| fitness.is_finite()).collect();
+    let n = trials.len() as f64;
+    let mean = trials.iter().map(|(_, fitness)| fitness).sum::<f64>() / n;
+    let total: f64 = trials.iter().map(|(_, fitness)| (fitness - mean).powi(2)).sum();
 
-            let mut test_params = best_params.clone();
+    let names = [
+        "swarm_size",
+        "max_iterations",
+        "inertia_weight",
+        "cognitive_weight",
+        "social_weight",
+        "num_runs",
+        "max_velocity",
+        "mutation_rate",
+    ];
+    let mut result: Vec<ParameterSensitivity> = names
+        .iter()
+        .filter_map(|name| {
+            let mut values: Vec<(f64, f64)> =
+                trials.iter().filter_map(|(params, fitness)| Some((get_param(params, name)?, *fitness))).collect();
+            values.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let distinct = values.windows(2).filter(|pair| pair[0].0 != pair[1].0).count() + 1;
+            if values.is_empty() || distinct < 2 {
+                return None;
+            }
 
-            match param_name {
-                "swarm_size" => test_params.swarm_size = *val as i32,
-                "max_iterations" => test_params.max_iterations = *val as usize,
-                "inertia_weight" => test_params.inertia_weight = *val,
-                "cognitive_weight" => test_params.cognitive_weight = *val,
-                "social_weight" => test_params.social_weight = *val,
-                _ => {}
+            // Neighbouring distinct values share a group, equal values always end up together
+            let groups = distinct.min(((values.len() as f64).sqrt().round() as usize).clamp(2, MAX_SENSITIVITY_GROUPS));
+            let mut sums = vec![(0.0, 0usize); groups];
+            let mut value_rank = 0;
+            for (rank, (value, fitness)) in values.iter().enumerate() {
+                if rank > 0 && *value != values[rank - 1].0 {
+                    value_rank += 1;
+                }
+                let group = value_rank * groups / distinct;
+                sums[group].0 += fitness;
+                sums[group].1 += 1;
             }
+            let between: f64 = sums
+                .iter()
+                .filter(|(_, count)| *count > 0)
+                .map(|(sum, count)| *count as f64 * (sum / *count as f64 - mean).powi(2))
+                .sum();
 
-            let fitness = run_fitness(&test_params, courses, time_preferences).await;
-            records.push((*val, fitness));
+            Some(ParameterSensitivity {
+                parameter: name.to_string(),
+                variance_explained: if total > 0.0 { between / total } else { 0.0 },
+                groups: sums.iter().filter(|(_, count)| *count > 0).count(),
+            })
+        })
+        .collect();
+    result.sort_by(|a, b| b.variance_explained.total_cmp(&a.variance_explained));
+    result
+}
 
-            full_experiments.entry(param_name.to_string())
-            .or_default()
-            .push((test_params.clone(), fitness));
+fn set_param(params: &mut PsoParameters, name: &str, value: f64) {
+    match name {
+        "swarm_size" => params.swarm_size = value as i32,
+        "max_iterations" => params.max_iterations = value as usize,
+        "inertia_weight" => params.inertia_weight = value,
+        "cognitive_weight" => params.cognitive_weight = value,
+        "social_weight" => params.social_weight = value,
+        "num_runs" => params.num_runs = Some(value as usize),
+        "max_velocity" => params.max_velocity = Some(value),
+        "mutation_rate" => params.mutation_rate = Some(value),
+        _ => {}
+    }
+}
 
+// Cartesian product of the grid steps
+fn grid_configurations(range: &ParamRange) -> Vec<PsoParameters> {
+    grid_steps(range, 1.0).into_iter().fold(vec![lower_bound(range)], |configurations, (name, values)| {
+        configurations
+            .iter()
+            .flat_map(|params| {
+                values.iter().map(move |value| {
+                    let mut params = params.clone();
+                    set_param(&mut params, name, *value);
+                    params
+                })
+            })
+            .collect()
+    })
+}
 
-            if fitness < best_fitness {
-                best_fitness = fitness;
-                best_val = *val;
-            }
+fn sample_params(range: &ParamRange, rng: &mut impl Rng) -> PsoParameters {
+    params_at(range, std::array::from_fn(|_| rng.random()))
+}
+
+// Latin hypercube design of `n` configurations: every dimension's range is cut into `n`
+// equal strata and each stratum holds exactly one of the configurations
+fn latin_hypercube(range: &ParamRange, n: usize, rng: &mut impl Rng) -> Vec<PsoParameters> {
+    let columns: Vec<Vec<f64>> = (0..PARAM_DIMENSIONS)
+        .map(|_| {
+            let mut strata: Vec<usize> = (0..n).collect();
+            strata.shuffle(rng);
+            strata.into_iter().map(|stratum| (stratum as f64 + rng.random::<f64>()) / n as f64).collect()
+        })
+        .collect();
+
+    (0..n).map(|i| params_at(range, std::array::from_fn(|dimension| columns[dimension][i]))).collect()
+}
+
+// Configuration at a point of the unit cube, one coordinate in [0, 1) per dimension
+fn params_at(range: &ParamRange, point: [f64; PARAM_DIMENSIONS]) -> PsoParameters {
+    // Two decimals are plenty for the weights and keep the reported values readable
+    let weight = |u: f64, (low, high): (f64, f64)| ((low + u * (high - low).max(0.0)) * 100.0).round() / 100.0;
+    // Every count in low..=high gets an equal share of the coordinate
+    let count = |u: f64, low: usize, high: usize| {
+        let high = high.max(low);
+        (low + (u * (high - low + 1) as f64) as usize).min(high)
+    };
+
+    PsoParameters {
+        inertia_weight: weight(point[0], range.inertia_weight),
+        cognitive_weight: weight(point[1], range.cognitive_weight),
+        social_weight: weight(point[2], range.social_weight),
+        max_velocity: range.max_velocity.map(|bounds| weight(point[3], bounds)),
+        mutation_rate: range.mutation_rate.map(|bounds| weight(point[4], bounds)),
+        swarm_size: count(point[5], range.swarm_size.0.max(0) as usize, range.swarm_size.1.max(0) as usize) as i32,
+        max_iterations: count(point[6], range.max_iterations.0, range.max_iterations.1),
+        num_runs: Some(range.num_runs.map_or(1, |(low, high)| count(point[7], low, high))),
+        ..lower_bound(range)
+    }
+}
+
+fn lower_bound(range: &ParamRange) -> PsoParameters {
+    PsoParameters {
+        swarm_size: range.swarm_size.0,
+        max_iterations: range.max_iterations.0,
+        inertia_weight: range.inertia_weight.0,
+        cognitive_weight: range.cognitive_weight.0,
+        social_weight: range.social_weight.0,
+        num_runs: Some(range.num_runs.map_or(1, |(low, _)| low)),
+        constraints: range.constraints.clone(),
+        penalty_weights: range.penalty_weights.clone(),
+        cache_size: None,
+        emit_every: None,
+        checkpoint_every: None,
+        max_velocity: range.max_velocity.map(|(low, _)| low),
+        mutation_rate: range.mutation_rate.map(|(low, _)| low),
+        seed: None,
+    }
+}
+
+// The configuration with its trial seed, derived from the master seed and the tuned values
+// so a trial gets the same seed whenever and in whatever order it runs. Setting that seed in
+// PsoParameters with repeats_per_trial 1 re-runs the trial exactly.
+fn with_trial_seed(params: PsoParameters, master: Option<u64>) -> PsoParameters {
+    let seed = master.map(|master| {
+        [
+            params.swarm_size as u64,
+            params.max_iterations as u64,
+            params.inertia_weight.to_bits(),
+            params.cognitive_weight.to_bits(),
+            params.social_weight.to_bits(),
+            params.num_runs.unwrap_or(1) as u64,
+            params.max_velocity.unwrap_or(0.0).to_bits(),
+            params.mutation_rate.unwrap_or(0.0).to_bits(),
+        ]
+        .into_iter()
+        .fold(master, derive_seed)
+            // Within 2^53, so the seed survives being a JSON number in the frontend
+            & ((1 << 53) - 1)
+    });
+    PsoParameters { seed, ..params }
+}
+
+// The parameters of the `index`-th run of a seeded configuration
+fn run_params(params: &PsoParameters, index: u64) -> PsoParameters {
+    PsoParameters { seed: params.seed.map(|seed| derive_seed(seed, index)), ..params.clone() }
+}
+
+// Mean and spread over `repeats` runs, with the convergence curve of each. Every repeat
+// starts from a different swarm, drawn from the trial seed when there is one.
+async fn run_fitness(
+    params: &PsoParameters,
+    datasets: &[Dataset],
+    repeats: usize,
+    stop_flag: Arc<AtomicBool>,
+) -> (RunStatistics, Vec<Vec<f64>>) {
+    println!(
+        "⚙️  Menjalankan fitness dengan params: swarm={}, iter={}, iw={:.2}, cw={:.2}, sw={:.2}",
+        params.swarm_size,
+        params.max_iterations,
+        params.inertia_weight,
+        params.cognitive_weight,
+        params.social_weight
+    );
+
+    let mut fitness = Vec::with_capacity(repeats);
+    let mut durations = Vec::with_capacity(repeats);
+    let mut convergence = Vec::with_capacity(repeats);
+    for repeat in 0..repeats {
+        if stop_flag.load(Ordering::Relaxed) {
+            break;
+        }
+        let start = Instant::now();
+        // num_runs runs make one measurement, keeping the best like process_pso does
+        let repeat_params = run_params(params, repeat as u64);
+        if let Some((run_fitness, curve)) =
+            run_datasets(&repeat_params, datasets, params.num_runs.unwrap_or(1), &stop_flag).await
+        {
+            fitness.push(run_fitness);
+            durations.push(start.elapsed());
+            convergence.push(curve);
         }
+    }
+
+    let statistics = RunStatistics::new(&fitness, &durations);
+    println!("📈 Fitness: {:.4} ± {:.4} ({} run)", statistics.mean, statistics.std_dev, fitness.len());
+    (statistics, convergence)
+}
 
-        match param_name {
-            "swarm_size" => best_params.swarm_size = best_val as i32,
-            "max_iterations" => best_params.max_iterations = best_val as usize,
-            "inertia_weight" => best_params.inertia_weight = best_val,
-            "cognitive_weight" => best_params.cognitive_weight = best_val,
-            "social_weight" => best_params.social_weight = best_val,
-            _ => {}
+// Best of `runs` runs on each dataset, averaged over the datasets, with the mean convergence
+// curve. None when a stop request left some dataset without a run, since such a mean is not
+// comparable.
+async fn run_datasets(
+    params: &PsoParameters,
+    datasets: &[Dataset],
+    runs: usize,
+    stop_flag: &Arc<AtomicBool>,
+) -> Option<(f64, Vec<f64>)> {
+    let mut fitness = Vec::with_capacity(datasets.len());
+    let mut curves = Vec::with_capacity(datasets.len());
+    for (index, dataset) in datasets.iter().enumerate() {
+        let mut best: Option<(f64, Vec<f64>)> = None;
+        let dataset_params = run_params(params, index as u64);
+        for run in 0..runs.max(1) {
+            let mut pso = PSO::new(dataset.courses.clone(), dataset.time_preferences.clone(), dataset_params.clone());
+            // The run number picks the run's swarm within the seed, as in process_pso
+            let (_, run_best) = pso.optimize(None, Some((run, runs)), &mut vec![], stop_flag.clone()).await;
+            if best.as_ref().is_none_or(|(best_fitness, _)| run_best < *best_fitness) {
+                best = Some((run_best, std::mem::take(&mut pso.convergence)));
+            }
+            if stop_flag.load(Ordering::Relaxed) {
+                break;
+            }
+        }
+        let (run_best, curve) = best?;
+        fitness.push(run_best);
+        curves.push(curve);
+        if stop_flag.load(Ordering::Relaxed) {
+            break;
         }
+    }
+    if fitness.is_empty() || fitness.len() < datasets.len() {
+        return None;
+    }
 
-        history.insert(param_name.to_string(), records);
+    // A curve ends early when its run stopped early; past its end it keeps its last value
+    let length = curves.iter().map(Vec::len).max().unwrap_or(0);
+    let curve = (0..length)
+        .map(|i| curves.iter().filter_map(|c| c.get(i).or(c.last())).sum::<f64>() / curves.len() as f64)
+        .collect();
+    Some((fitness.iter().sum::<f64>() / fitness.len() as f64, curve))
+}
+
+// One point of a convergence curve, in long format so every trial plots as its own line
+#[derive(Serialize)]
+struct ConvergenceRow {
+    trial: usize,
+    repeat: usize,
+    swarm_size: i32,
+    max_iterations: usize,
+    inertia_weight: f64,
+    cognitive_weight: f64,
+    social_weight: f64,
+    num_runs: Option<usize>,
+    max_velocity: Option<f64>,
+    mutation_rate: Option<f64>,
+    seed: Option<u64>,
+    iteration: usize,
+    best_fitness: f64,
+}
 
-        println!(
-            "✅ Parameter {} terbaik: {} dengan fitness {:.4}",
-            param_name, best_val, best_fitness
-        );
+fn export_convergence(param_range: &ParamRange, log: &TrialLog) {
+    let Some(path) = &param_range.convergence_file else { return };
+    match write_convergence(path, &log.trials) {
+        Ok(()) => println!("📉 Kurva konvergensi disimpan di {}", path.display()),
+        Err(e) => eprintln!("⚠️  Gagal menyimpan kurva konvergensi: {}", e),
     }
+}
 
+fn write_convergence(path: &Path, trials: &[TrialRecord]) -> Result<(), String> {
+    let mut writer = csv::Writer::from_path(path).map_err(|e| e.to_string())?;
+    for (trial, record) in trials.iter().enumerate() {
+        let params = &record.params;
+        for (repeat, curve) in record.convergence.iter().enumerate() {
+            for (iteration, best_fitness) in curve.iter().enumerate() {
+                writer
+                    .serialize(ConvergenceRow {
+                        trial: trial + 1,
+                        repeat: repeat + 1,
+                        swarm_size: params.swarm_size,
+                        max_iterations: params.max_iterations,
+                        inertia_weight: params.inertia_weight,
+                        cognitive_weight: params.cognitive_weight,
+                        social_weight: params.social_weight,
+                        num_runs: params.num_runs,
+                        max_velocity: params.max_velocity,
+                        mutation_rate: params.mutation_rate,
+                        seed: params.seed,
+                        iteration: iteration + 1,
+                        best_fitness: *best_fitness,
+                    })
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    writer.flush().map_err(|e| e.to_string())
+}
+
+// Several runs with the chosen parameters, so the final fitness comes with its spread
+async fn final_runs(
+    datasets: &[Dataset],
+    best_params: PsoParameters,
+    overall_best: f64,
+    experiments: Value,
+    stop_flag: Arc<AtomicBool>,
+) -> Value {
     println!("\n🚀 Menjalankan optimasi akhir dengan parameter terbaik...");
 
-    let mut pso = PSO::new(courses.to_vec(), time_preferences.to_vec(), best_params.clone());
-    let (_, fitness) = pso
-        .optimize(None, None, &mut vec![], Arc::new(AtomicBool::new(false)))
-        .await;
+    let mut final_fitness = Vec::with_capacity(FINAL_RUNS);
+    let mut durations = Vec::with_capacity(FINAL_RUNS);
+    // A stream of their own, so the final runs do not repeat the trial's runs
+    let final_params = run_params(&best_params, u64::MAX);
+    for run in 0..FINAL_RUNS {
+        if stop_flag.load(Ordering::Relaxed) {
+            break;
+        }
+
+        let start = Instant::now();
+        if let Some((fitness, _)) = run_datasets(&run_params(&final_params, run as u64), datasets, 1, &stop_flag).await {
+            final_fitness.push(fitness);
+            durations.push(start.elapsed());
+        }
+    }
+    let statistics = RunStatistics::new(&final_fitness, &durations);
+    let fitness = if final_fitness.is_empty() { overall_best } else { statistics.best };
 
     println!("🏁 Optimasi selesai. Final Fitness: {:.4}", fitness);
 
-    let json_result = json!({
+    json!({
         "fitness": fitness,
+        "statistics": statistics,
         "best_params": best_params,
-        "experiments": history,
-    });
+        "experiments": experiments,
+        "stopped_early": stop_flag.load(Ordering::Relaxed),
+    })
+}
+
+// The problem the recorded tuning runs (pengujian_pso.xlsx) were done on: data/course.csv
+const TUNED_COURSES: usize = 287;
+const TUNED_LECTURERS: usize = 59;
+
+/// Starting parameters for a problem of this size, meant as a sane default before any tuning,
+/// with the given constraints and penalty weights left as they are.
+///
+/// The values are the best ones of the one-parameter-at-a-time runs in pengujian_pso.xlsx, all
+/// on data/course.csv (287 courses, 59 lecturers) with one run per configuration:
+/// - inertia 0.8 scored 900, against 1400-2500 for 0.5, 0.6, 0.7 and 0.9
+/// - cognitive 1.4 scored 500 and social 1.0 scored 600 (tied with 1.3), both over 1.0-2.9,
+///   with the fitness rising steadily above 1.5
+/// - swarm 100 scored 1400 against 1500-1800 for 200-500, and 1000 iterations did no better
+///   than 500 (1700 both)
+///
+/// Only that size was measured. Elsewhere the swarm and iterations scale linearly with the
+/// courses, and the iterations also with the courses per lecturer; that scaling is an
+/// assumption, so a problem far from 287 courses is worth tuning.
+pub fn suggest_parameters(
+    num_courses: usize,
+    num_lecturers: usize,
+    constraints: ConstraintConfig,
+    penalty_weights: PenaltyWeights,
+) -> PsoParameters {
+    let size = num_courses as f64 / TUNED_COURSES as f64;
+    let density = (num_courses as f64 / num_lecturers.max(1) as f64) / (TUNED_COURSES as f64 / TUNED_LECTURERS as f64);
 
-    (json_result, full_experiments)
+    // Larger swarms did not help on the measured size, so 100 is also the upper bound
+    let swarm_size = ((100.0 * size) as i32).clamp(20, 100);
+    let max_iterations = ((500.0 * size * density) as usize).clamp(100, 1000).next_multiple_of(50);
+
+    PsoParameters {
+        swarm_size,
+        max_iterations,
+        inertia_weight: 0.8,
+        cognitive_weight: 1.4,
+        social_weight: 1.0,
+        num_runs: Some(1),
+        constraints,
+        penalty_weights,
+        cache_size: None,
+        emit_every: None,
+        checkpoint_every: None,
+        max_velocity: None,
+        mutation_rate: None,
+        seed: None,
+    }
 }
@@ -0,0 +1,89 @@
+use std::collections::{HashMap, HashSet};
+
+use super::models::{
+    ConstraintConfig, CourseRequest, IssueLevel, ValidationIssue, ValidationReport, DEFAULT_WAKTU,
+};
+use super::preference::parse_preference_csv;
+
+/// Checks the input CSVs before a run, collecting every problem instead of stopping at the first
+pub fn validate_input(course_csv: &str, preference_csv: &str, config: &ConstraintConfig) -> ValidationReport {
+    let mut issues = Vec::new();
+
+    let mut rdr = csv::Reader::from_reader(course_csv.as_bytes());
+    let mut courses: Vec<CourseRequest> = Vec::new();
+    for (row, result) in rdr.deserialize::<CourseRequest>().enumerate() {
+        match result {
+            Ok(course) => courses.push(course),
+            Err(e) => issues.push(issue(IssueLevel::Error, "course_parse_error", None, format!("Baris {}: {}", row + 2, e))),
+        }
+    }
+
+    let dosen_with_preferences: Option<HashSet<u32>> = match parse_preference_csv(preference_csv) {
+        Ok(prefs) => Some(prefs.iter().map(|p| p.id_dosen).collect()),
+        Err(e) => {
+            issues.push(issue(IssueLevel::Error, "preference_parse_error", None, e.to_string()));
+            None
+        }
+    };
+
+    let mut seen: HashMap<(u32, u32), usize> = HashMap::new();
+    for course in &courses {
+        *seen.entry((course.id_jadwal, course.pertemuan)).or_default() += 1;
+    }
+    let mut duplicates: Vec<_> = seen.into_iter().filter(|(_, count)| *count > 1).collect();
+    duplicates.sort();
+    for ((id_jadwal, _), count) in duplicates {
+        issues.push(issue(
+            IssueLevel::Error,
+            "duplicate_id_jadwal",
+            Some(id_jadwal),
+            format!("id_jadwal {} muncul {} kali.", id_jadwal, count),
+        ));
+    }
+
+    let windows: Vec<u32> = match config.waktu.as_deref() {
+        Some(waktu) if !waktu.is_empty() => waktu.iter().map(|w| w.id_waktu).collect(),
+        _ => DEFAULT_WAKTU.iter().map(|w| w.id_waktu).collect(),
+    };
+    let mut missing_dosen: HashSet<u32> = HashSet::new();
+
+    for course in &courses {
+        if course.sks == 0 {
+            issues.push(issue(
+                IssueLevel::Error,
+                "zero_sks",
+                Some(course.id_jadwal),
+                format!("Jadwal {} memiliki 0 SKS.", course.id_jadwal),
+            ));
+        }
+
+        if !windows.contains(&course.id_waktu) {
+            issues.push(issue(
+                IssueLevel::Error,
+                "unknown_id_waktu",
+                Some(course.id_jadwal),
+                format!("Jadwal {} memakai id_waktu {} yang tidak dikenal.", course.id_jadwal, course.id_waktu),
+            ));
+        }
+
+        if let Some(known) = &dosen_with_preferences {
+            if !known.contains(&course.id_dosen) && missing_dosen.insert(course.id_dosen) {
+                issues.push(issue(
+                    IssueLevel::Warning,
+                    "missing_preference",
+                    Some(course.id_jadwal),
+                    format!("Dosen {} tidak memiliki data preferensi, semua slot dianggap boleh.", course.id_dosen),
+                ));
+            }
+        }
+    }
+
+    ValidationReport {
+        valid: issues.iter().all(|i| i.tingkat != IssueLevel::Error),
+        issues,
+    }
+}
+
+fn issue(tingkat: IssueLevel, kode: &'static str, id_jadwal: Option<u32>, pesan: String) -> ValidationIssue {
+    ValidationIssue { tingkat, kode, id_jadwal, pesan }
+}
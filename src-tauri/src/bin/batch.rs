@@ -0,0 +1,122 @@
+// Optimizes several departments in one invocation, from a JSON manifest such as
+//
+//   {
+//     "params": { ... },            optional, PsoParameters for every department
+//     "departments": [
+//       { "name": "informatika", "course_csv": "if/course.csv", "preference_csv": "if/preference.csv" },
+//       { "name": "sipil", "course_csv": "ts/course.csv", "preference_csv": "ts/preference.csv",
+//         "lecturer_csv": "ts/dosen.csv", "absence_csv": "ts/absen.csv", "params": { ... } }
+//     ]
+//   }
+//
+// Ctrl+C stops the running departments; their best schedule so far is still written.
+use std::path::PathBuf;
+use std::sync::{atomic::{AtomicBool, Ordering}, Arc};
+
+use clap::Parser;
+use tokio::runtime::Runtime;
+
+use schedule_optimization_lib::algorithms::batch::{run_batch, BatchManifest};
+use schedule_optimization_lib::algorithms::export::SCHEDULE_FORMATS;
+
+/// Optimasi beberapa departemen sekaligus dari satu manifest JSON
+#[derive(Parser)]
+#[command(name = "batch")]
+struct Options {
+    /// Manifest JSON berisi daftar departemen
+    manifest: PathBuf,
+    /// Folder untuk <nama>.<format> per departemen dan ringkasan.csv
+    #[arg(long, default_value = "hasil_batch")]
+    out: PathBuf,
+    /// csv, json atau xlsx
+    #[arg(long, default_value = "xlsx", value_parser = parse_format)]
+    format: String,
+    /// Departemen yang dioptimasi bersamaan
+    #[arg(long, default_value_t = 1)]
+    parallel: usize,
+}
+
+fn parse_format(format: &str) -> Result<String, String> {
+    let format = format.to_lowercase();
+    if !SCHEDULE_FORMATS.contains(&format.as_str()) {
+        return Err("pakai csv, json atau xlsx".to_string());
+    }
+    Ok(format)
+}
+
+fn main() {
+    let options = Options::parse();
+
+    let manifest = BatchManifest::load(&options.manifest).unwrap_or_else(|e| {
+        eprintln!("❌ {}", e);
+        std::process::exit(2);
+    });
+    let total = manifest.departments.len();
+    println!("📥 {} departemen dari {}", total, options.manifest.display());
+
+    let rt = Runtime::new().expect("Gagal membuat Tokio runtime");
+
+    // Ctrl+C ends the batch early; departments already running still write their best schedule
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let ctrl_c_flag = stop_flag.clone();
+    rt.spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            println!("\n⏹️  Menghentikan batch...");
+            ctrl_c_flag.store(true, Ordering::Relaxed);
+        }
+    });
+
+    println!("🚀 Mulai optimasi, {} departemen sekaligus...", options.parallel.max(1));
+    let mut done = 0;
+    let result = rt.block_on(run_batch(manifest, &options.out, &options.format, options.parallel, stop_flag, |department| {
+        done += 1;
+        match (&department.message, department.fitness) {
+            (Some(message), _) => println!("❌ [{}/{}] {}: {}", done, total, department.name, message),
+            (None, fitness) => println!(
+                "✅ [{}/{}] {}: fitness {:.2}, {}, {:.0} detik",
+                done,
+                total,
+                department.name,
+                fitness.unwrap_or_default(),
+                if department.is_feasible { "feasible" } else { "tidak feasible" },
+                department.durasi
+            ),
+        }
+    }));
+
+    let summary = result.unwrap_or_else(|e| {
+        eprintln!("❌ {}", e);
+        std::process::exit(1);
+    });
+
+    println!("\n=== Ringkasan Batch ===");
+    if summary.stopped_early {
+        println!("(dihentikan sebelum selesai, departemen yang belum mulai dilewati)");
+    }
+    println!("{:<20} {:>8} {:>12} {:>9} {:>6}", "Departemen", "Course", "Fitness", "Feasible", "Hard");
+    for department in &summary.departments {
+        match department.fitness {
+            Some(fitness) => println!(
+                "{:<20} {:>8} {:>12.2} {:>9} {:>6}",
+                department.name,
+                department.courses,
+                fitness,
+                if department.is_feasible { "ya" } else { "tidak" },
+                department.hard_violations
+            ),
+            None => println!("{:<20} gagal: {}", department.name, department.message.as_deref().unwrap_or_default()),
+        }
+    }
+    println!(
+        "\n{} feasible, {} gagal dari {} departemen dalam {:.0} detik",
+        summary.feasible,
+        summary.failed,
+        summary.departments.len(),
+        summary.durasi
+    );
+    println!("💾 Ringkasan disimpan ke {}", summary.summary);
+
+    if summary.failed > 0 {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,207 @@
+// Optimizes one schedule from the command line, for batch scheduling on a server without
+// the desktop app. Ctrl+C stops the run and still writes the best schedule so far.
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::{atomic::{AtomicBool, Ordering}, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use indicatif::{ProgressBar, ProgressStyle};
+use tokio::runtime::Runtime;
+
+use schedule_optimization_lib::algorithms::export::{write_schedule, SCHEDULE_FORMATS};
+use schedule_optimization_lib::algorithms::models::{CourseRequest, JobState, JobStatus, PsoParameters, ScheduleChecker, PSO};
+use schedule_optimization_lib::algorithms::preference::read_input;
+use schedule_optimization_lib::algorithms::tune::suggest_parameters;
+
+/// Optimasi satu jadwal dari command line
+#[derive(Parser)]
+#[command(name = "optimize")]
+struct Options {
+    /// File CSV course
+    #[arg(long, default_value = "data/course.csv")]
+    courses: String,
+    /// File CSV preferensi dosen
+    #[arg(long, default_value = "data/preference.csv")]
+    prefs: String,
+    /// File CSV data dosen
+    #[arg(long)]
+    lecturers: Option<String>,
+    /// File CSV absen dosen
+    #[arg(long)]
+    absences: Option<String>,
+    /// PsoParameters dalam JSON; tanpa ini parameter dipilih dari ukuran input
+    #[arg(long)]
+    params: Option<String>,
+    #[arg(long = "swarm")]
+    swarm_size: Option<i32>,
+    #[arg(long = "iterations")]
+    max_iterations: Option<usize>,
+    #[arg(long = "runs")]
+    num_runs: Option<usize>,
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Jadwal hasil dalam .csv, .json atau .xlsx, dipilih dari ekstensinya
+    #[arg(long, default_value = "jadwal.xlsx", value_parser = parse_output)]
+    out: String,
+}
+
+fn parse_output(out: &str) -> Result<String, String> {
+    let extension = Path::new(out).extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+    if !SCHEDULE_FORMATS.contains(&extension.as_str()) {
+        return Err("pakai .csv, .json atau .xlsx".to_string());
+    }
+    Ok(out.to_string())
+}
+
+fn read_file(path: &str) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("Gagal membaca file {}: {}", path, e))
+}
+
+// Parameters from --params, or suggested from the input size; single flags override both
+fn parameters(options: &Options, courses: &[CourseRequest]) -> Result<PsoParameters, String> {
+    let mut params = match &options.params {
+        Some(path) => serde_json::from_str(&read_file(path)?).map_err(|e| format!("Gagal parse {}: {}", path, e))?,
+        None => {
+            let lecturers: HashSet<u32> = courses.iter().map(|c| c.id_dosen).collect();
+            suggest_parameters(courses.len(), lecturers.len(), Default::default(), Default::default())
+        }
+    };
+    if let Some(swarm_size) = options.swarm_size {
+        params.swarm_size = swarm_size;
+    }
+    if let Some(max_iterations) = options.max_iterations {
+        params.max_iterations = max_iterations;
+    }
+    if let Some(num_runs) = options.num_runs {
+        params.num_runs = Some(num_runs);
+    }
+    if options.seed.is_some() {
+        params.seed = options.seed;
+    }
+    if params.swarm_size <= 0 || params.max_iterations == 0 {
+        return Err("swarm_size dan max_iterations harus lebih dari 0".to_string());
+    }
+    Ok(params)
+}
+
+fn main() {
+    let options = Options::parse();
+
+    println!("📥 Membaca file CSV...");
+    let (courses, prefs) = read_input(
+        Path::new(&options.courses),
+        Path::new(&options.prefs),
+        options.lecturers.as_deref().map(Path::new),
+        options.absences.as_deref().map(Path::new),
+    )
+    .unwrap_or_else(|e| {
+        eprintln!("❌ {}", e);
+        std::process::exit(1);
+    });
+    let params = parameters(&options, &courses).unwrap_or_else(|e| {
+        eprintln!("❌ {}", e);
+        std::process::exit(2);
+    });
+    let num_runs = params.num_runs.unwrap_or(1);
+    println!("✅ Jumlah course: {}, prefs: {}", courses.len(), prefs.len());
+    println!(
+        "⚙️  swarm {}, iterasi {}, run {}, inertia {}, cognitive {}, social {}{}",
+        params.swarm_size,
+        params.max_iterations,
+        num_runs,
+        params.inertia_weight,
+        params.cognitive_weight,
+        params.social_weight,
+        params.seed.map(|seed| format!(", seed {}", seed)).unwrap_or_default()
+    );
+
+    let rt = Runtime::new().expect("Gagal membuat Tokio runtime");
+
+    // Ctrl+C ends the run early; the best schedule so far is still written
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let ctrl_c_flag = stop_flag.clone();
+    rt.spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            println!("\n⏹️  Menghentikan optimasi...");
+            ctrl_c_flag.store(true, Ordering::Relaxed);
+        }
+    });
+
+    // The optimizer updates this every iteration, the progress bar polls it
+    let status = Arc::new(Mutex::new(JobStatus {
+        job_id: 0,
+        state: JobState::Running,
+        iteration: 0,
+        max_iterations: params.max_iterations,
+        current_run: 0,
+        total_runs: num_runs,
+        best_fitness: f64::INFINITY,
+        result: None,
+        checkpoint_id: None,
+        started_at: chrono::Local::now(),
+        finished_at: None,
+    }));
+
+    let pb = ProgressBar::new((params.max_iterations * num_runs) as u64);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner:.cyan} [{bar:40.cyan/blue}] {pos}/{len} iterasi ({eta}) {msg}")
+            .unwrap()
+            .progress_chars("=> "),
+    );
+    let finished = Arc::new(AtomicBool::new(false));
+    let progress = {
+        let (pb, status, finished) = (pb.clone(), status.clone(), finished.clone());
+        std::thread::spawn(move || {
+            while !finished.load(Ordering::Relaxed) {
+                let status = status.lock().unwrap().clone();
+                pb.set_position(status.iterations_done() as u64);
+                if status.best_fitness.is_finite() {
+                    pb.set_message(format!("run {}/{}, fitness {:.2}", status.current_run + 1, num_runs, status.best_fitness));
+                }
+                std::thread::sleep(Duration::from_millis(100));
+            }
+        })
+    };
+
+    println!("🚀 Mulai proses optimasi PSO...");
+    let start_time = Instant::now();
+    let best = rt.block_on(PSO::optimize_best(courses, prefs.clone(), &params, stop_flag.clone(), |pso| {
+        pso.status = Some(status.clone());
+    }));
+
+    finished.store(true, Ordering::Relaxed);
+    progress.join().unwrap();
+    pb.finish_with_message("✅ Proses optimasi selesai.");
+
+    let elapsed = start_time.elapsed();
+    println!("🕒 Waktu total: {} menit {} detik", elapsed.as_secs() / 60, elapsed.as_secs() % 60);
+
+    let (schedule, fitness) = best.unwrap_or_else(|e| {
+        eprintln!("❌ {}", e);
+        std::process::exit(1);
+    });
+
+    let checker = ScheduleChecker::new(prefs, params.constraints.clone(), params.penalty_weights.clone());
+    let evaluation = checker.evaluate(&schedule);
+    let report = checker.evaluate_messages(&schedule);
+
+    println!("=== Hasil Optimasi PSO ===");
+    if stop_flag.load(Ordering::Relaxed) {
+        println!("(dihentikan sebelum selesai, hasil dari run yang sudah berjalan)");
+    }
+    println!("Fitness          : {:.2}", fitness);
+    println!("Feasible         : {}", if evaluation.is_feasible() { "ya" } else { "tidak" });
+    println!("Pelanggaran hard : {}", evaluation.hard_violations);
+    println!("Penalti soft     : {:.2}", evaluation.soft_penalty);
+    println!("Konflik          : {}", report.conflicts.len());
+    println!("Preferensi       : {} tidak terpenuhi", report.preference_violations.len());
+
+    if let Err(e) = write_schedule(&schedule, Path::new(&options.out)) {
+        eprintln!("❌ {}", e);
+        std::process::exit(1);
+    }
+    println!("💾 Jadwal disimpan ke {}", options.out);
+}
@@ -1,13 +1,17 @@
 use schedule_optimization_lib::algorithms::models::{
-    CourseRequest, PsoParameters, TimePreferenceRequest
+    CourseRequest, PsoParameters
 };
+use schedule_optimization_lib::algorithms::preference::parse_preference_csv;
 
 use indicatif::{ProgressBar, ProgressStyle};
+use std::sync::{atomic::{AtomicBool, Ordering}, Arc};
 use std::time::Instant;
 
-use schedule_optimization_lib::algorithms::tune::{optimize_by_range, ParamRange};
+use schedule_optimization_lib::algorithms::tune::{optimize_by_range, Dataset, ParamRange, TuneStrategy};
 
+use clap::Parser;
 use std::fs;
+use std::path::PathBuf;
 use tokio::runtime::Runtime;
 
 use rust_xlsxwriter::{Workbook, Format, XlsxError};
@@ -15,6 +19,7 @@ use std::collections::HashMap;
 
 pub fn export_full_experiments_single_sheet(
     data: &HashMap<String, Vec<(PsoParameters, f64)>>,
+    sensitivity: &[serde_json::Value],
     filename: &str,
 ) -> Result<(), XlsxError> {
     let mut workbook = Workbook::new();
@@ -29,6 +34,7 @@ pub fn export_full_experiments_single_sheet(
         "cognitive_weight",
         "social_weight",
         "fitness",
+        "seed",
     ];
 
     let mut current_row = 0;
@@ -53,12 +59,31 @@ pub fn export_full_experiments_single_sheet(
             worksheet.write(current_row, 3, params.cognitive_weight)?;
             worksheet.write(current_row, 4, params.social_weight)?;
             worksheet.write(current_row, 5, *fitness)?;
+            if let Some(seed) = params.seed {
+                worksheet.write(current_row, 6, seed.to_string())?;
+            }
             current_row += 1;
         }
 
         current_row += 2; // Spacer antar sub-tabel
     }
 
+    // Parameter yang paling menentukan fitness, dari semua percobaan di atas
+    if !sensitivity.is_empty() {
+        worksheet.write_with_format(current_row, 0, "=== Sensitivitas Parameter ===", &bold)?;
+        current_row += 1;
+        for (col, title) in ["parameter", "variance_explained", "groups"].iter().enumerate() {
+            worksheet.write_with_format(current_row, col as u16, *title, &bold)?;
+        }
+        current_row += 1;
+        for entry in sensitivity {
+            worksheet.write(current_row, 0, entry["parameter"].as_str().unwrap_or_default())?;
+            worksheet.write(current_row, 1, entry["variance_explained"].as_f64().unwrap_or_default())?;
+            worksheet.write(current_row, 2, entry["groups"].as_u64().unwrap_or_default() as u32)?;
+            current_row += 1;
+        }
+    }
+
     workbook.save(filename)?;
     Ok(())
 }
@@ -71,31 +96,227 @@ fn parse_course_csv(csv: &str) -> Result<Vec<CourseRequest>, String> {
         .collect()
 }
 
-fn parse_preference_csv(csv: &str) -> Result<Vec<TimePreferenceRequest>, String> {
-    let mut rdr = csv::Reader::from_reader(csv.as_bytes());
-    rdr.deserialize()
-        .map(|result| result.map_err(|e| format!("Preference CSV error: {}", e)))
-        .collect()
+// Every finished trial is saved here; run with --resume to continue an interrupted search
+const CHECKPOINT_FILE: &str = "pengujian_pso.checkpoint.json";
+
+// A range from the command line with its grid step, kept apart since ParamRange keeps the
+// steps apart from the ranges
+type RangeArg<T> = ((T, T), Option<f64>);
+
+/// Mencari parameter PSO terbaik dalam rentang yang diberikan dan menyimpan semua percobaan ke Excel
+#[derive(Parser)]
+#[command(name = "parameter_search")]
+struct Args {
+    /// File CSV course
+    #[arg(long, default_value = "data/course.csv")]
+    courses: String,
+    /// File CSV preferensi dosen
+    #[arg(long, default_value = "data/preference.csv")]
+    prefs: String,
+    /// Pasangan course,preference tambahan untuk menilai parameter; boleh diulang
+    #[arg(long = "dataset", value_name = "COURSE,PREFERENCE", value_parser = parse_dataset)]
+    datasets: Vec<(String, String)>,
+    /// File Excel berisi semua percobaan
+    #[arg(long, default_value = "pengujian_pso.xlsx")]
+    out: String,
+    /// Rentang swarm size, low..high[:step]
+    #[arg(long, default_value = "100..500", value_parser = parse_range::<i32>)]
+    swarm: RangeArg<i32>,
+    /// Rentang max iterations, low..high[:step]
+    #[arg(long, default_value = "500..1000", value_parser = parse_range::<usize>)]
+    iterations: RangeArg<usize>,
+    /// Rentang inertia weight, low..high[:step]
+    #[arg(long, default_value = "0.5..0.9", value_parser = parse_range::<f64>)]
+    inertia: RangeArg<f64>,
+    /// Rentang cognitive weight, low..high[:step]
+    #[arg(long, default_value = "1..3", value_parser = parse_range::<f64>)]
+    cognitive: RangeArg<f64>,
+    /// Rentang social weight, low..high[:step]
+    #[arg(long, default_value = "1..3", value_parser = parse_range::<f64>)]
+    social: RangeArg<f64>,
+    /// Rentang jumlah run per percobaan; tanpa opsi ini tidak ikut dicari
+    #[arg(long, value_parser = parse_range::<usize>)]
+    num_runs: Option<RangeArg<usize>>,
+    /// Rentang max velocity; tanpa opsi ini tidak ikut dicari
+    #[arg(long, value_parser = parse_range::<f64>)]
+    max_velocity: Option<RangeArg<f64>>,
+    /// Rentang mutation rate; tanpa opsi ini tidak ikut dicari
+    #[arg(long, value_parser = parse_range::<f64>)]
+    mutation_rate: Option<RangeArg<f64>>,
+    /// coordinate_descent, random_search, latin_hypercube atau grid
+    #[arg(long, value_parser = parse_strategy)]
+    strategy: Option<TuneStrategy>,
+    /// Jumlah sampel untuk random_search dan latin_hypercube
+    #[arg(long)]
+    samples: Option<usize>,
+    /// Jumlah putaran coordinate descent
+    #[arg(long)]
+    passes: Option<usize>,
+    /// Run PSO yang dirata-rata per konfigurasi
+    #[arg(long)]
+    repeats: Option<usize>,
+    /// Percobaan yang dijalankan bersamaan
+    #[arg(long)]
+    parallel: Option<usize>,
+    /// Berhenti setelah sekian percobaan
+    #[arg(long)]
+    max_trials: Option<usize>,
+    /// Tidak memulai percobaan baru setelah sekian detik
+    #[arg(long)]
+    max_seconds: Option<u64>,
+    #[arg(long)]
+    seed: Option<u64>,
+    /// File JSON tempat setiap percobaan yang selesai disimpan
+    #[arg(long, default_value = CHECKPOINT_FILE)]
+    checkpoint: PathBuf,
+    /// CSV fitness terbaik per iterasi dari setiap percobaan
+    #[arg(long, default_value = "konvergensi_pso.csv")]
+    convergence: PathBuf,
+    /// Lanjutkan dari percobaan yang sudah ada di file checkpoint
+    #[arg(long)]
+    resume: bool,
+    /// Saring konfigurasi dengan iterasi lebih sedikit, hanya yang terbaik dijalankan penuh
+    #[arg(long)]
+    racing: bool,
+    /// Izinkan grid search dengan percobaan sangat banyak
+    #[arg(long)]
+    confirm_large_grid: bool,
 }
 
-fn main() {
-    println!("📥 Membaca file CSV...");
-    let course_csv = fs::read_to_string("data/course.csv").expect("Gagal membaca file course.csv");
-    let preference_csv = fs::read_to_string("data/preference.csv").expect("Gagal membaca file preference.csv");
+struct Options {
+    courses: String,
+    prefs: String,
+    datasets: Vec<(String, String)>,
+    out: String,
+    param_range: ParamRange,
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("bukan angka: {}", value))
+}
+
+// `low..high`, `low..high:step` or a single value
+fn parse_range<T: std::str::FromStr + Copy>(value: &str) -> Result<RangeArg<T>, String> {
+    let (bounds, step) = match value.split_once(':') {
+        Some((bounds, step)) => (bounds, Some(parse_number(step)?)),
+        None => (value, None),
+    };
+    let range = match bounds.split_once("..") {
+        Some((low, high)) => (parse_number(low)?, parse_number(high)?),
+        None => {
+            let value = parse_number(bounds)?;
+            (value, value)
+        }
+    };
+    Ok((range, step))
+}
+
+fn parse_dataset(value: &str) -> Result<(String, String), String> {
+    let (courses, prefs) = value.split_once(',').ok_or("butuh course.csv,preference.csv")?;
+    Ok((courses.to_string(), prefs.to_string()))
+}
+
+fn parse_strategy(value: &str) -> Result<TuneStrategy, String> {
+    serde_json::from_value(serde_json::Value::String(value.to_string())).map_err(|_| "strategi tidak dikenal".to_string())
+}
 
-    println!("✅ File CSV berhasil dibaca.");
+fn options() -> Result<Options, String> {
+    let args = Args::parse();
 
-    let courses = parse_course_csv(&course_csv).expect("Gagal parse course.csv");
-    let prefs = parse_preference_csv(&preference_csv).expect("Gagal parse preference.csv");
+    let steps = [
+        ("swarm_size", args.swarm.1),
+        ("max_iterations", args.iterations.1),
+        ("inertia_weight", args.inertia.1),
+        ("cognitive_weight", args.cognitive.1),
+        ("social_weight", args.social.1),
+        ("num_runs", args.num_runs.and_then(|(_, step)| step)),
+        ("max_velocity", args.max_velocity.and_then(|(_, step)| step)),
+        ("mutation_rate", args.mutation_rate.and_then(|(_, step)| step)),
+    ]
+    .into_iter()
+    .filter_map(|(name, step)| Some((name.to_string(), step?)))
+    .collect();
+
+    let param_range = ParamRange {
+        swarm_size: args.swarm.0,
+        max_iterations: args.iterations.0,
+        inertia_weight: args.inertia.0,
+        cognitive_weight: args.cognitive.0,
+        social_weight: args.social.0,
+        num_runs: args.num_runs.map(|(range, _)| range),
+        max_velocity: args.max_velocity.map(|(range, _)| range),
+        mutation_rate: args.mutation_rate.map(|(range, _)| range),
+        strategy: args.strategy.unwrap_or_default(),
+        samples: args.samples,
+        passes: args.passes,
+        repeats_per_trial: args.repeats,
+        parallelism: args.parallel,
+        max_trials: args.max_trials,
+        max_seconds: args.max_seconds,
+        seed: args.seed,
+        checkpoint_file: Some(args.checkpoint),
+        convergence_file: Some(args.convergence),
+        resume: args.resume,
+        racing: args.racing,
+        confirm_large_grid: args.confirm_large_grid,
+        steps,
+        ..ParamRange::default()
+    };
+    param_range.check_trial_count()?;
+
+    Ok(Options {
+        courses: args.courses,
+        prefs: args.prefs,
+        datasets: args.datasets,
+        out: args.out,
+        param_range,
+    })
+}
+
+// Reads one course/preference pair
+fn load_dataset(course_file: &str, preference_file: &str) -> Dataset {
+    let course_csv = fs::read_to_string(course_file).unwrap_or_else(|e| panic!("Gagal membaca file {}: {}", course_file, e));
+    let preference_csv =
+        fs::read_to_string(preference_file).unwrap_or_else(|e| panic!("Gagal membaca file {}: {}", preference_file, e));
+
+    let courses = parse_course_csv(&course_csv).unwrap_or_else(|e| panic!("Gagal parse {}: {}", course_file, e));
+    let prefs = parse_preference_csv(&preference_csv).unwrap_or_else(|e| panic!("Gagal parse {}: {}", preference_file, e));
 
     println!(
-        "✅ Data berhasil di-parse. Jumlah course: {}, prefs: {}",
+        "✅ {}: Jumlah course: {}, prefs: {}",
+        course_file,
         courses.len(),
         prefs.len()
     );
+    Dataset { courses, time_preferences: prefs }
+}
+
+fn main() {
+    let options = options().unwrap_or_else(|e| {
+        eprintln!("❌ {}", e);
+        std::process::exit(2);
+    });
+
+    // Every --dataset adds a pair the parameters are scored on
+    println!("📥 Membaca file CSV...");
+    let mut datasets = vec![load_dataset(&options.courses, &options.prefs)];
+    for (course_file, preference_file) in &options.datasets {
+        datasets.push(load_dataset(course_file, preference_file));
+    }
+    println!("✅ {} dataset berhasil di-parse.", datasets.len());
 
     let rt = Runtime::new().expect("Gagal membuat Tokio runtime");
 
+    // Ctrl+C ends the search early; the trials done so far are still reported and exported
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let ctrl_c_flag = stop_flag.clone();
+    rt.spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            println!("\n⏹️  Menghentikan pencarian parameter...");
+            ctrl_c_flag.store(true, Ordering::Relaxed);
+        }
+    });
+
     println!("🚀 Mulai proses optimasi PSO...");
 
     // Mulai hitung waktu total
@@ -113,15 +334,10 @@ fn main() {
     );
 
     let (result, full_experiments) = rt.block_on(optimize_by_range(
-        &courses,
-        &prefs,
-        ParamRange {
-            swarm_size: (100, 500),
-            max_iterations: (500, 1000),
-            inertia_weight: (0.5, 0.9),
-            cognitive_weight: (1.0, 3.0),
-            social_weight: (1.0, 3.0),
-        },
+        datasets,
+        options.param_range,
+        stop_flag,
+        |_| {},
     ));
 
     pb.finish_with_message("✅ Proses optimasi selesai.");
@@ -134,11 +350,22 @@ fn main() {
 
     // Cetak hasil dari JSON
     println!("=== Hasil Optimasi PSO ===");
+    if result["stopped_early"].as_bool().unwrap_or(false) {
+        println!("(dihentikan sebelum selesai, hasil dari trial yang sudah berjalan)");
+    }
     println!(
         "\nFitness: {:.2}",
         result["fitness"].as_f64().unwrap_or_default()
     );
 
+    if let Some(stats) = result.get("statistics") {
+        println!("\n=== Statistik {} Run Akhir ===", stats["durations"].as_array().map_or(0, |d| d.len()));
+        println!("Mean           : {:.2}", stats["mean"].as_f64().unwrap_or_default());
+        println!("Std Dev        : {:.2}", stats["std_dev"].as_f64().unwrap_or_default());
+        println!("Median         : {:.2}", stats["median"].as_f64().unwrap_or_default());
+        println!("Best / Worst   : {:.2} / {:.2}", stats["best"].as_f64().unwrap_or_default(), stats["worst"].as_f64().unwrap_or_default());
+    }
+
     println!("\n=== Parameter Terbaik ===");
     if let Some(params) = result.get("best_params") {
         println!("Swarm Size     : {}", params["swarm_size"]);
@@ -148,5 +375,17 @@ fn main() {
         println!("Social W       : {}", params["social_weight"]);
     }
 
-    export_full_experiments_single_sheet(&full_experiments, "pengujian_pso.xlsx").unwrap();
+    let sensitivity = result["sensitivity"].as_array().cloned().unwrap_or_default();
+    if !sensitivity.is_empty() {
+        println!("\n=== Sensitivitas Parameter ===");
+        for entry in &sensitivity {
+            println!(
+                "{:<16} : {:.1}%",
+                entry["parameter"].as_str().unwrap_or_default(),
+                entry["variance_explained"].as_f64().unwrap_or_default() * 100.0
+            );
+        }
+    }
+
+    export_full_experiments_single_sheet(&full_experiments, &sensitivity, &options.out).unwrap();
 }
@@ -0,0 +1,98 @@
+// Runs only the HTTP API, for deployment on a server without the desktop app.
+// Every option can come from the environment or the command line, the latter winning:
+//
+//   --addr <host:port>        SCHEDULE_SERVER_ADDR        (default 127.0.0.1:3000)
+//   --workers <n>             SCHEDULE_SERVER_WORKERS     jobs optimized at the same time
+//   --queue <n>               SCHEDULE_SERVER_QUEUE       jobs that may wait for a worker
+//   --api-key <key>           SCHEDULE_SERVER_API_KEYS    repeatable / comma-separated
+//   --result-ttl <seconds>    SCHEDULE_SERVER_RESULT_TTL  how long finished results are kept
+//   --rate-limit <n>          SCHEDULE_SERVER_RATE_LIMIT  /optimize calls per client per minute, 0 = off
+//   --max-jobs <n>            SCHEDULE_SERVER_MAX_JOBS    active jobs per client, 0 = off
+//   --database <file>         SCHEDULE_SERVER_DB          SQLite file keeping jobs across restarts
+//
+// Ctrl+C, SIGTERM or POST /admin/shutdown stop the running jobs and store their best
+// schedule so far before the process exits.
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use schedule_optimization_lib::algorithms::handlers::{serve, ServerConfig};
+
+const USAGE: &str = "Penggunaan: schedule-server [--addr host:port] [--workers n] [--queue n] [--api-key key]... [--result-ttl detik] [--rate-limit n] [--max-jobs n] [--database file]";
+
+struct Options {
+    addr: SocketAddr,
+    config: ServerConfig,
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("Nilai {} tidak valid: {}", name, value))
+}
+
+fn api_keys(keys: &str) -> impl Iterator<Item = String> + '_ {
+    keys.split(',').map(str::trim).filter(|key| !key.is_empty()).map(str::to_string)
+}
+
+fn options() -> Result<Options, String> {
+    let env = |name: &str| std::env::var(name).ok();
+    let defaults = ServerConfig::default();
+
+    let mut addr = env("SCHEDULE_SERVER_ADDR").unwrap_or_else(|| "127.0.0.1:3000".to_string());
+    let mut workers = env("SCHEDULE_SERVER_WORKERS").map(|v| parse_number("workers", &v)).transpose()?;
+    let mut queue_size = env("SCHEDULE_SERVER_QUEUE").map(|v| parse_number("queue", &v)).transpose()?;
+    let mut result_ttl = env("SCHEDULE_SERVER_RESULT_TTL").map(|v| parse_number("result-ttl", &v)).transpose()?;
+    let mut rate_limit = env("SCHEDULE_SERVER_RATE_LIMIT").map(|v| parse_number("rate-limit", &v)).transpose()?;
+    let mut max_jobs = env("SCHEDULE_SERVER_MAX_JOBS").map(|v| parse_number("max-jobs", &v)).transpose()?;
+    let mut database = env("SCHEDULE_SERVER_DB").map(PathBuf::from);
+    let mut keys: Vec<String> = env("SCHEDULE_SERVER_API_KEYS").map(|v| api_keys(&v).collect()).unwrap_or_default();
+    let mut cli_keys = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        if flag == "--help" || flag == "-h" {
+            println!("{}", USAGE);
+            std::process::exit(0);
+        }
+        let value = args.next().ok_or_else(|| format!("{} membutuhkan nilai\n{}", flag, USAGE))?;
+        match flag.as_str() {
+            "--addr" => addr = value,
+            "--workers" => workers = Some(parse_number("workers", &value)?),
+            "--queue" => queue_size = Some(parse_number("queue", &value)?),
+            "--result-ttl" => result_ttl = Some(parse_number("result-ttl", &value)?),
+            "--rate-limit" => rate_limit = Some(parse_number("rate-limit", &value)?),
+            "--max-jobs" => max_jobs = Some(parse_number("max-jobs", &value)?),
+            "--database" => database = Some(PathBuf::from(value)),
+            "--api-key" => cli_keys.extend(api_keys(&value)),
+            _ => return Err(format!("Opsi tidak dikenal: {}\n{}", flag, USAGE)),
+        }
+    }
+    if !cli_keys.is_empty() {
+        keys = cli_keys;
+    }
+
+    Ok(Options {
+        addr: addr.parse().map_err(|_| format!("Alamat tidak valid: {}", addr))?,
+        config: ServerConfig {
+            workers: workers.unwrap_or(defaults.workers),
+            queue_size: queue_size.unwrap_or(defaults.queue_size),
+            api_keys: keys,
+            result_ttl: result_ttl.map(Duration::from_secs).unwrap_or(defaults.result_ttl),
+            requests_per_minute: rate_limit.unwrap_or(defaults.requests_per_minute),
+            max_jobs_per_client: max_jobs.unwrap_or(defaults.max_jobs_per_client),
+            database,
+        },
+    })
+}
+
+#[tokio::main]
+async fn main() {
+    let options = options().unwrap_or_else(|e| {
+        eprintln!("❌ {}", e);
+        std::process::exit(2);
+    });
+
+    if let Err(e) = serve(options.addr, options.config).await {
+        eprintln!("❌ Server berhenti: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -1,99 +1,756 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
 pub mod algorithms;
-use algorithms::models::{ CourseRequest, OptimizedCourse, PSO, PsoParameters, ScheduleChecker, TimePreferenceRequest};
+use algorithms::models::{
+    Checkpoint, ConstraintConfig, CourseRequest, InputSource, JobEvent, JobId, JobState, JobStatus, LecturerPreference, OptimizedCourse, PauseControl, PenaltyWeights, PSO,
+    PsoParameters, RunRecord, RunStatistics, ScheduleChecker, ScheduleEdit, SemesterCalendar, SwarmState, TimePreferenceRequest,
+};
+use algorithms::batch::{run_batch, BatchManifest};
+use algorithms::checkpoint::CheckpointStore;
+use algorithms::error::ScheduleError;
+use algorithms::preference::parse_csv;
+use calamine::{open_workbook_auto_from_rs, Data, Reader};
+use algorithms::history::{input_hash, RunHistory};
 
-use std::{f64, sync::{atomic::{AtomicBool, Ordering}, Arc, Mutex}};
-use tauri::State;
+use std::{
+    collections::HashMap,
+    f64,
+    sync::{atomic::{AtomicBool, AtomicU64, Ordering}, Arc, Mutex},
+    time::Instant,
+};
+use algorithms::tune::{optimize_by_range, Dataset, ParamRange};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+// Finished desktop jobs kept for get_status, the oldest are dropped beyond this
+const MAX_FINISHED_JOBS: usize = 10;
 
 #[derive(Default)]
 pub struct AppState {
-    pub stop_flag: Mutex<Option<Arc<AtomicBool>>>,
+    pub jobs: Mutex<HashMap<JobId, Arc<Job>>>,
+    pub next_job_id: AtomicU64,
+    pub last_run: Mutex<Option<LastRun>>,
 }
 
-#[tauri::command]
-fn stop_pso(state: State<'_, AppState>) {
-    if let Some(flag) = &*state.stop_flag.lock().unwrap() {
-        flag.store(true, Ordering::Relaxed);
+// A process_pso or tune_parameters call. Kept after it finishes so get_status
+// can still report the result, up to MAX_FINISHED_JOBS of them.
+pub struct Job {
+    pub id: JobId,
+    pub stop_flag: Arc<AtomicBool>,
+    pub pause: Arc<PauseControl>,
+    pub status: Arc<Mutex<JobStatus>>,
+}
+
+impl Job {
+    pub(crate) fn finish(&self, result: Value) {
+        let mut status = self.status.lock().unwrap();
+        status.state = if self.stop_flag.load(Ordering::Relaxed) { JobState::Stopped } else { JobState::Finished };
+        status.result = Some(result);
+        status.finished_at = Some(chrono::Local::now());
+    }
+
+    // Current status, reporting a run waiting on pause_pso as paused
+    pub(crate) fn snapshot(&self) -> JobStatus {
+        let mut status = self.status.lock().unwrap().clone();
+        if status.state == JobState::Running && *self.pause.paused.lock().unwrap() {
+            status.state = JobState::Paused;
+        }
+        status
+    }
+}
+
+impl AppState {
+    pub(crate) fn start_job(&self) -> Arc<Job> {
+        let id = self.next_job_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let job = Arc::new(Job {
+            id,
+            stop_flag: Arc::new(AtomicBool::new(false)),
+            pause: Arc::new(PauseControl::default()),
+            status: Arc::new(Mutex::new(JobStatus {
+                job_id: id,
+                state: JobState::Running,
+                iteration: 0,
+                max_iterations: 0,
+                current_run: 0,
+                total_runs: 0,
+                best_fitness: f64::INFINITY,
+                result: None,
+                checkpoint_id: None,
+                started_at: chrono::Local::now(),
+                finished_at: None,
+            })),
+        });
+        self.jobs.lock().unwrap().insert(id, job.clone());
+        job
+    }
+
+    // Drops the jobs that finished longest ago past MAX_FINISHED_JOBS, each holds its whole result.
+    // The server expires its jobs by age instead, see handlers::expire_results.
+    pub(crate) fn evict_finished_jobs(&self) {
+        let mut jobs = self.jobs.lock().unwrap();
+        let mut finished: Vec<_> = jobs
+            .values()
+            .filter_map(|job| job.status.lock().unwrap().finished_at.map(|at| (at, job.id)))
+            .collect();
+        if finished.len() <= MAX_FINISHED_JOBS {
+            return;
+        }
+
+        finished.sort();
+        for (_, id) in &finished[..finished.len() - MAX_FINISHED_JOBS] {
+            jobs.remove(id);
+        }
+    }
+
+    pub(crate) fn job(&self, job_id: JobId) -> Result<Arc<Job>, ScheduleError> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .get(&job_id)
+            .cloned()
+            .ok_or_else(|| ScheduleError::invalid(format!("Job {} tidak ditemukan", job_id)))
     }
 }
 
+// Best result of the last process_pso call, kept for explain_placement
+pub struct LastRun {
+    pub courses: Vec<CourseRequest>,
+    pub position: Vec<f64>,
+    pub schedule: Vec<OptimizedCourse>,
+    pub checker: ScheduleChecker,
+}
+
 #[tauri::command]
-async fn process_pso(
-    course_csv: String,
-    preference_csv: String,
+fn stop_pso(job_id: JobId, state: State<'_, AppState>) -> Result<(), ScheduleError> {
+    state.job(job_id)?.stop_flag.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+#[tauri::command]
+fn pause_pso(job_id: JobId, state: State<'_, AppState>) -> Result<(), ScheduleError> {
+    state.job(job_id)?.pause.pause();
+    Ok(())
+}
+
+#[tauri::command]
+fn resume_pso(job_id: JobId, state: State<'_, AppState>) -> Result<(), ScheduleError> {
+    state.job(job_id)?.pause.resume();
+    Ok(())
+}
+
+// Latest progress of a job for polling, e.g. after the frontend reloads
+#[tauri::command]
+fn get_status(job_id: JobId, state: State<'_, AppState>) -> Result<JobStatus, ScheduleError> {
+    Ok(state.job(job_id)?.snapshot())
+}
+
+// Starts the optimization in the background and returns its job id right away.
+// Progress comes as "optimization-progress" events, the result as a "pso-finished" event,
+// both carrying the job id. Input errors are still returned directly.
+#[tauri::command]
+fn process_pso(
+    course_csv: InputSource<CourseRequest>,
+    preference_csv: InputSource<TimePreferenceRequest>,
+    lecturer_csv: Option<String>,
+    absence_csv: Option<String>,
     params: PsoParameters,
     window: tauri::Window,
-    state: State<'_, AppState>, // Tambahan
-) -> Result<Value, String> {
-    let courses = parse_course_csv(&course_csv)?;
-    let time_preferences = parse_preference_csv(&preference_csv)?;
-    let num_runs: usize = params.num_runs.unwrap_or(1);
+    state: State<'_, AppState>,
+) -> Result<JobId, ScheduleError> {
+    let dibuat = chrono::Local::now();
+    let checkpoint = Checkpoint {
+        id: dibuat.format("%Y%m%d-%H%M%S-%3f").to_string(),
+        dibuat,
+        course_csv: read_input(course_csv)?,
+        preference_csv: read_input(preference_csv)?,
+        lecturer_csv,
+        absence_csv,
+        params,
+        current_run: 0,
+        all_best_fitness: Vec::new(),
+        run_durations: Vec::new(),
+        best_run: None,
+    };
+
+    start_pso(checkpoint, None, window, &state)
+}
+
+// Continues a job interrupted by closing the app, from the given or the most recent checkpoint.
+// Behaves like process_pso from there on, under a new job id.
+#[tauri::command]
+fn resume_from_checkpoint(
+    checkpoint_id: Option<String>,
+    window: tauri::Window,
+    state: State<'_, AppState>,
+) -> Result<JobId, ScheduleError> {
+    let (checkpoint, swarm) = checkpoint_store(window.app_handle())?.load(checkpoint_id.as_deref())?;
+    start_pso(checkpoint, Some(swarm), window, &state)
+}
+
+fn start_pso(
+    checkpoint: Checkpoint,
+    swarm: Option<SwarmState>,
+    window: tauri::Window,
+    state: &AppState,
+) -> Result<JobId, ScheduleError> {
+    let params = &checkpoint.params;
+    if params.swarm_size <= 0 || params.max_iterations == 0 {
+        return Err(ScheduleError::invalid("swarm_size dan max_iterations harus lebih dari 0"));
+    }
+
+    let courses = parse_course_csv(&checkpoint.course_csv)?;
+    let time_preferences = load_preferences(
+        &checkpoint.preference_csv,
+        checkpoint.lecturer_csv.clone(),
+        checkpoint.absence_csv.clone(),
+    )?;
 
-    let stop_flag = Arc::new(AtomicBool::new(false));
-    {
-        let mut flag = state.stop_flag.lock().unwrap();
-        *flag = Some(stop_flag.clone());
+    let job = state.start_job();
+    if checkpoint.params.checkpoint_every.is_some() {
+        job.status.lock().unwrap().checkpoint_id = Some(checkpoint.id.clone());
     }
 
+    let job_id = job.id;
+    // CPU-bound and blocking while paused, so kept off the async runtime's worker threads
+    tauri::async_runtime::spawn_blocking(move || {
+        let result = tauri::async_runtime::block_on(run_pso(&job, checkpoint, swarm, courses, time_preferences, &window));
+        let result = finished_event(job.id, result);
+        job.finish(result.clone());
+        let _ = window.emit("pso-finished", result);
+        window.state::<AppState>().evict_finished_jobs();
+    });
+
+    Ok(job_id)
+}
+
+async fn run_pso(
+    job: &Job,
+    checkpoint: Checkpoint,
+    mut swarm: Option<SwarmState>,
+    courses: Vec<CourseRequest>,
+    time_preferences: Vec<LecturerPreference>,
+    window: &tauri::Window,
+) -> Result<Value, ScheduleError> {
+    let params = &checkpoint.params;
+    let stop_flag = &job.stop_flag;
+    let num_runs: usize = params.num_runs.unwrap_or(1);
+    let started = Instant::now();
+
     let mut best_overall_schedule: Option<Vec<OptimizedCourse>> = None;
+    let mut best_overall_run: Option<(Vec<f64>, Vec<CourseRequest>)> = None;
     let mut best_overall_fitness = f64::INFINITY;
-    let mut all_best_fitness = Vec::with_capacity(num_runs);
+    let mut all_best_fitness = checkpoint.all_best_fitness.clone();
+    let mut run_durations = checkpoint.run_durations.clone();
+    let mut stopped_early = false;
+
+    // Best of the runs that finished before the checkpoint
+    if let Some((position, fitness)) = &checkpoint.best_run {
+        let split = PSO::split_courses(courses.clone(), params.constraints.batas_pecah_sks);
+        best_overall_fitness = *fitness;
+        best_overall_schedule = Some(PSO::position_to_schedule(position, &split, &params.constraints));
+        best_overall_run = Some((position.clone(), split));
+    }
+
+    // Checkpointing is skipped, not fatal, when the app data dir is unavailable
+    let store = params
+        .checkpoint_every
+        .and_then(|_| checkpoint_store(window.app_handle()).map_err(|e| eprintln!("⚠️  {}", e)).ok())
+        .map(Arc::new);
 
-    for i in 0..num_runs {
+    for i in checkpoint.current_run..num_runs {
         let mut pso = PSO::new(
             courses.clone(),
             time_preferences.clone(),
             params.clone(),
         );
+        pso.pause = Some(job.pause.clone());
+        pso.job_id = Some(job.id);
+        pso.status = Some(job.status.clone());
+        pso.resume_from = swarm.take();
 
+        if let Some(store) = &store {
+            let store = store.clone();
+            let meta = Checkpoint {
+                current_run: i,
+                all_best_fitness: all_best_fitness.clone(),
+                run_durations: run_durations.clone(),
+                best_run: best_overall_run.as_ref().map(|(position, _)| (position.clone(), best_overall_fitness)),
+                ..checkpoint.clone()
+            };
+            pso.on_checkpoint = Some(Box::new(move |swarm| {
+                if let Err(e) = store.save(&meta, swarm) {
+                    eprintln!("⚠️  {}", e);
+                }
+            }));
+        }
+
+        let run_start = Instant::now();
         let (best_position, fitness) =
-            pso.optimize(Some(&window), Some((i, num_runs)), &mut all_best_fitness, stop_flag.clone()).await;
+            pso.optimize(Some(window), Some((i, num_runs)), &mut all_best_fitness, stop_flag.clone()).await;
+        run_durations.push(run_start.elapsed());
 
-        if stop_flag.load(Ordering::Relaxed) {
-            break; // keluar dari loop jika dihentikan
+        // The interrupted run's global best still competes, it is only left out of the statistics
+        let stopped = stop_flag.load(Ordering::Relaxed);
+        if stopped {
+            stopped_early = true;
+            all_best_fitness.pop();
+            run_durations.pop();
         }
 
-        let schedule = PSO::position_to_schedule(&best_position, &courses);
-
         if fitness < best_overall_fitness {
             best_overall_fitness = fitness;
-            best_overall_schedule = Some(schedule);
+            best_overall_schedule = Some(PSO::position_to_schedule(&best_position, &pso.courses, &params.constraints));
+            best_overall_run = Some((best_position, pso.courses.clone()));
+        }
+
+        if stopped {
+            break; // keluar dari loop jika dihentikan
         }
     }
 
-    let conflicts = if let Some(ref schedule) = best_overall_schedule {
-        let checker = ScheduleChecker::new(time_preferences.clone());
-        checker.evaluate_messages(schedule)
+    // The job ran to its end (or was stopped on purpose), nothing left to resume
+    if let Some(store) = &store {
+        if let Err(e) = store.remove(&checkpoint.id) {
+            eprintln!("⚠️  {}", e);
+        }
+    }
+
+    let checker = ScheduleChecker::new(time_preferences.clone(), params.constraints.clone(), params.penalty_weights.clone());
+    let (evaluation, conflicts, room_utilization, satisfaction) = if let Some(ref schedule) = best_overall_schedule {
+        (
+            checker.evaluate(schedule),
+            checker.evaluate_messages(schedule),
+            checker.room_utilization(schedule),
+            checker.preference_satisfaction(schedule),
+        )
     } else {
-        (vec![], vec![]) // fallback kosong jika tidak ada jadwal
+        Default::default() // fallback kosong jika tidak ada jadwal
     };
 
+    let report = serde_json::to_value(&conflicts).map_err(|e| e.to_string())?;
+
+    // Gagal menyimpan riwayat tidak menggagalkan hasil optimasi
+    let run_id = best_overall_schedule.as_ref().and_then(|schedule| {
+        let dibuat = chrono::Local::now();
+        let record = RunRecord {
+            id: dibuat.format("%Y%m%d-%H%M%S-%3f").to_string(),
+            dibuat,
+            input_hash: input_hash(&[
+                Some(&checkpoint.course_csv),
+                Some(&checkpoint.preference_csv),
+                checkpoint.lecturer_csv.as_deref(),
+                checkpoint.absence_csv.as_deref(),
+            ]),
+            params: params.clone(),
+            fitness: best_overall_fitness,
+            durasi: started.elapsed().as_secs_f64(),
+            schedule: schedule.clone(),
+        };
+        match run_history(window.app_handle()).and_then(|history| history.save(&record)) {
+            Ok(()) => Some(record.id),
+            Err(e) => {
+                eprintln!("⚠️  {}", e);
+                None
+            }
+        }
+    });
+
     let result = json!({
         "success": true,
+        "stopped_early": stopped_early,
+        "run_id": run_id,
         "fitness": best_overall_fitness,
+        "normalized_score": best_overall_schedule.as_ref().map_or(0.0, |schedule| evaluation.normalized(schedule.len())),
+        "is_feasible": best_overall_schedule.is_some() && evaluation.is_feasible(),
+        "hard_violations": evaluation.hard_violations,
+        "soft_penalty": evaluation.soft_penalty,
         "all_best_fitness": all_best_fitness,
+        "statistics": RunStatistics::new(&all_best_fitness, &run_durations),
         "schedule": best_overall_schedule,
-        "message": conflicts
+        "room_utilization": room_utilization,
+        "preference_satisfaction": satisfaction,
+        "conflicts": report["conflicts"],
+        "preference_violations": report["preference_violations"],
+        "message": report
     });
 
+    if best_overall_schedule.is_none() && stopped_early {
+        return Err(ScheduleError::Stopped);
+    }
+
+    if let (Some(schedule), Some((position, courses))) = (best_overall_schedule, best_overall_run) {
+        *window.state::<AppState>().last_run.lock().unwrap() = Some(LastRun { courses, position, schedule, checker });
+    }
+
     Ok(result)
 }
 
+// Another course/preference pair for tune_parameters to score configurations on
+#[derive(Deserialize)]
+pub struct DatasetCsv {
+    course_csv: String,
+    preference_csv: String,
+}
+
+// Searches PSO parameters over the given range in the background, like process_pso.
+// With extra_datasets every configuration is scored by its mean fitness over all pairs.
+// One "tuning-progress" event per trial and a "tuning-finished" event with the result.
+#[tauri::command]
+fn tune_parameters(
+    course_csv: String,
+    preference_csv: String,
+    extra_datasets: Option<Vec<DatasetCsv>>,
+    param_range: ParamRange,
+    window: tauri::Window,
+    state: State<'_, AppState>,
+) -> Result<JobId, ScheduleError> {
+    param_range.check_trial_count().map_err(ScheduleError::invalid)?;
+    let datasets = std::iter::once(DatasetCsv { course_csv, preference_csv })
+        .chain(extra_datasets.unwrap_or_default())
+        .map(|dataset| {
+            Ok(Dataset {
+                courses: parse_course_csv(&dataset.course_csv)?,
+                time_preferences: algorithms::preference::parse_preference_csv(&dataset.preference_csv)?,
+            })
+        })
+        .collect::<Result<Vec<_>, ScheduleError>>()?;
+
+    let job = state.start_job();
+    let job_id = job.id;
+    // The trials and final runs are CPU-bound, kept off the async runtime like process_pso
+    tauri::async_runtime::spawn_blocking(move || {
+        let (result, _) = tauri::async_runtime::block_on(optimize_by_range(datasets, param_range, job.stop_flag.clone(), |progress| {
+            {
+                let mut status = job.status.lock().unwrap();
+                status.iteration = progress.trial;
+                status.max_iterations = progress.total_trials;
+                status.best_fitness = progress.best_fitness;
+            }
+            let _ = window.emit("tuning-progress", JobEvent { job_id, data: progress.clone() });
+        }));
+        let result = finished_event(job_id, Ok(result));
+        job.finish(result.clone());
+        let _ = window.emit("tuning-finished", result);
+        window.state::<AppState>().evict_finished_jobs();
+    });
+
+    Ok(job_id)
+}
+
+// Optimizes every department of a JSON manifest in the background, `parallel` at a time,
+// writing one schedule per department and the combined ringkasan.csv into out_dir.
+// One "batch-progress" event per finished department and a "batch-finished" event with the summary.
+#[tauri::command]
+fn optimize_batch(
+    manifest: String,
+    out_dir: String,
+    format: Option<String>,
+    parallel: Option<usize>,
+    window: tauri::Window,
+    state: State<'_, AppState>,
+) -> Result<JobId, ScheduleError> {
+    let manifest = BatchManifest::load(std::path::Path::new(&manifest))?;
+    let format = format.unwrap_or_else(|| "xlsx".to_string()).to_lowercase();
+    if !algorithms::export::SCHEDULE_FORMATS.contains(&format.as_str()) {
+        return Err(ScheduleError::invalid(format!("Format output tidak dikenal: {} (pakai csv, json atau xlsx)", format)));
+    }
+
+    let job = state.start_job();
+    let job_id = job.id;
+    job.status.lock().unwrap().max_iterations = manifest.departments.len();
+    tauri::async_runtime::spawn(async move {
+        let mut done = 0;
+        let result = run_batch(manifest, std::path::Path::new(&out_dir), &format, parallel.unwrap_or(1), job.stop_flag.clone(), |summary| {
+            done += 1;
+            job.status.lock().unwrap().iteration = done;
+            let _ = window.emit("batch-progress", JobEvent { job_id, data: summary.clone() });
+        })
+        .await
+        .and_then(|summary| serde_json::to_value(summary).map_err(|e| ScheduleError::from(e.to_string())));
+        let result = finished_event(job_id, result);
+        job.finish(result.clone());
+        let _ = window.emit("batch-finished", result);
+        window.state::<AppState>().evict_finished_jobs();
+    });
+
+    Ok(job_id)
+}
+
+// Result object of a finished job with its id, or {success: false, message, error} when it failed
+pub(crate) fn finished_event(job_id: JobId, result: Result<Value, ScheduleError>) -> Value {
+    let mut event = result.unwrap_or_else(|e| json!({ "success": false, "message": e.to_string(), "error": e }));
+    event["job_id"] = json!(job_id);
+    event
+}
+
+// Why a course of the last optimization result landed in its slot
+#[tauri::command]
+fn explain_placement(id_jadwal: u32, state: State<'_, AppState>) -> Result<Value, ScheduleError> {
+    let last_run = state.last_run.lock().unwrap();
+    let Some(run) = last_run.as_ref() else {
+        return Err(ScheduleError::invalid("Belum ada hasil optimasi untuk dijelaskan"));
+    };
+
+    let explanations = PSO::explain(&run.position, &run.courses, &run.schedule, &run.checker, id_jadwal);
+    if explanations.is_empty() {
+        return Err(ScheduleError::invalid(format!("Jadwal {} tidak ditemukan pada hasil optimasi", id_jadwal)));
+    }
+
+    Ok(json!(explanations))
+}
+
+// Structured warnings/errors for the input CSVs, meant to run before process_pso
+#[tauri::command]
+fn validate_input(course_csv: String, preference_csv: String, constraints: Option<ConstraintConfig>) -> Value {
+    json!(algorithms::validation::validate_input(
+        &course_csv,
+        &preference_csv,
+        &constraints.unwrap_or_default(),
+    ))
+}
+
+// Checks a given (e.g. manually edited) schedule without running PSO
+#[tauri::command]
+fn evaluate_schedule(
+    schedule: String,
+    preference_csv: String,
+    lecturer_csv: Option<String>,
+    absence_csv: Option<String>,
+    constraints: Option<ConstraintConfig>,
+    penalty_weights: Option<PenaltyWeights>,
+) -> Result<Value, ScheduleError> {
+    let schedule = parse_schedule(&schedule)?;
+    let time_preferences = load_preferences(&preference_csv, lecturer_csv, absence_csv)?;
+
+    let checker = ScheduleChecker::new(
+        time_preferences,
+        constraints.unwrap_or_default(),
+        penalty_weights.unwrap_or_default(),
+    );
+    let evaluation = checker.evaluate(&schedule);
+
+    Ok(json!({
+        "fitness": evaluation.fitness(),
+        "is_feasible": evaluation.is_feasible(),
+        "hard_violations": evaluation.hard_violations,
+        "soft_penalty": evaluation.soft_penalty,
+        "conflicts": checker.detect_conflicts(&schedule),
+        "preferences": checker.check_preferences(&schedule),
+        "message": checker.evaluate_messages(&schedule)
+    }))
+}
+
+// Diff of two candidate schedules, e.g. from different runs
+#[tauri::command]
+fn compare_schedules(
+    schedule_a: String,
+    schedule_b: String,
+    preference_csv: String,
+    lecturer_csv: Option<String>,
+    absence_csv: Option<String>,
+    constraints: Option<ConstraintConfig>,
+    penalty_weights: Option<PenaltyWeights>,
+) -> Result<Value, ScheduleError> {
+    let schedule_a = parse_schedule(&schedule_a)?;
+    let schedule_b = parse_schedule(&schedule_b)?;
+    let time_preferences = load_preferences(&preference_csv, lecturer_csv, absence_csv)?;
+
+    let checker = ScheduleChecker::new(
+        time_preferences,
+        constraints.unwrap_or_default(),
+        penalty_weights.unwrap_or_default(),
+    );
+
+    Ok(json!(checker.compare(&schedule_a, &schedule_b)))
+}
+
+// What-if for a manual edit: swaps two courses' slots or moves one, without changing the input
+#[tauri::command]
+fn try_swap(
+    schedule: String,
+    edit: ScheduleEdit,
+    preference_csv: String,
+    lecturer_csv: Option<String>,
+    absence_csv: Option<String>,
+    constraints: Option<ConstraintConfig>,
+    penalty_weights: Option<PenaltyWeights>,
+) -> Result<Value, ScheduleError> {
+    let schedule = parse_schedule(&schedule)?;
+    let time_preferences = load_preferences(&preference_csv, lecturer_csv, absence_csv)?;
+
+    let checker = ScheduleChecker::new(
+        time_preferences,
+        constraints.unwrap_or_default(),
+        penalty_weights.unwrap_or_default(),
+    );
+
+    Ok(json!(checker.try_edit(&schedule, &edit).map_err(ScheduleError::invalid)?))
+}
+
+// Writes a schedule (e.g. the result of process_pso or get_run) to a formatted workbook
+#[tauri::command]
+fn export_schedule_xlsx(schedule: String, path: String) -> Result<(), ScheduleError> {
+    let schedule = parse_schedule(&schedule)?;
+    algorithms::export::export_schedule_xlsx(&schedule, &path)
+        .map_err(|e| ScheduleError::from(format!("Excel export error: {}", e)))
+}
+
+// Weekly recurring events for the semester, one .ics per dosen and per kelas in `dir`
+#[tauri::command]
+fn export_ics(schedule: String, kalender: SemesterCalendar, dir: String) -> Result<Value, ScheduleError> {
+    let schedule = parse_schedule(&schedule)?;
+    Ok(json!(algorithms::export::export_ics(&schedule, &kalender, std::path::Path::new(&dir))?))
+}
+
+// Printable weekly timetable, one page per class and per dosen
+#[tauri::command]
+fn export_pdf(schedule: String, path: String) -> Result<(), ScheduleError> {
+    let schedule = parse_schedule(&schedule)?;
+    Ok(algorithms::export::export_pdf(&schedule, std::path::Path::new(&path))?)
+}
+
+// Default PSO parameters scaled to the problem, for users who have not tuned anything yet.
+// The constraints and weights already set in the form are kept.
+#[tauri::command]
+fn suggest_parameters(
+    num_courses: usize,
+    num_lecturers: usize,
+    constraints: Option<ConstraintConfig>,
+    penalty_weights: Option<PenaltyWeights>,
+) -> PsoParameters {
+    algorithms::tune::suggest_parameters(
+        num_courses,
+        num_lecturers,
+        constraints.unwrap_or_default(),
+        penalty_weights.unwrap_or_default(),
+    )
+}
+
+// Example course.csv and preference.csv in `dir`, to start from headers that parse
+#[tauri::command]
+fn generate_templates(dir: String) -> Result<Value, ScheduleError> {
+    Ok(json!(algorithms::template::write_templates(std::path::Path::new(&dir))?))
+}
+
+// Past runs from the history store, newest first
+#[tauri::command]
+fn list_runs(app: AppHandle) -> Result<Value, ScheduleError> {
+    Ok(json!(run_history(&app)?.list()?))
+}
+
+// Full record of a past run, including its schedule, for review or re-export
+#[tauri::command]
+fn get_run(id: String, app: AppHandle) -> Result<Value, ScheduleError> {
+    Ok(json!(run_history(&app)?.get(&id)?))
+}
+
+fn run_history(app: &AppHandle) -> Result<RunHistory, String> {
+    let dir = app.path().app_data_dir().map_err(|e| format!("App data dir error: {}", e))?;
+    Ok(RunHistory::new(dir.join("runs")))
+}
+
+fn checkpoint_store(app: &AppHandle) -> Result<CheckpointStore, String> {
+    let dir = app.path().app_data_dir().map_err(|e| format!("App data dir error: {}", e))?;
+    Ok(CheckpointStore::new(dir.join("checkpoints")))
+}
+
 // Helper functions for parsing
-fn parse_course_csv(csv: &str) -> Result<Vec<CourseRequest>, String> {
-    let mut rdr = csv::Reader::from_reader(csv.as_bytes());
-    rdr.deserialize()
-        .map(|result| result.map_err(|e| format!("CSV parse error: {}", e)))
-        .collect()
+pub(crate) fn load_preferences(
+    preference_csv: &str,
+    lecturer_csv: Option<String>,
+    absence_csv: Option<String>,
+) -> Result<Vec<LecturerPreference>, ScheduleError> {
+    let mut time_preferences = algorithms::preference::parse_preference_csv(preference_csv)?;
+    if let Some(lecturer_csv) = lecturer_csv {
+        let lecturers = algorithms::preference::parse_lecturer_csv(&lecturer_csv)?;
+        algorithms::preference::apply_lecturers(&mut time_preferences, &lecturers);
+    }
+    if let Some(absence_csv) = absence_csv {
+        let absences = algorithms::preference::parse_absence_csv(&absence_csv)?;
+        algorithms::preference::apply_absences(&mut time_preferences, &absences);
+    }
+
+    Ok(time_preferences)
+}
+
+// Schedule as the JSON array returned by process_pso, or as CSV with the same columns
+pub(crate) fn parse_schedule(data: &str) -> Result<Vec<OptimizedCourse>, ScheduleError> {
+    if data.trim_start().starts_with('[') {
+        return serde_json::from_str(data).map_err(|e| ScheduleError::ParseError {
+            row: None,
+            column: None,
+            message: format!("Schedule JSON error: {}", e),
+        });
+    }
+
+    parse_csv(data, "Schedule CSV")
+}
+
+// CSV text of an input. Workbooks and typed rows are converted so everything goes through
+// the same CSV parsers, and a checkpoint can store the input as text.
+fn read_input<T: Serialize>(source: InputSource<T>) -> Result<String, ScheduleError> {
+    let bytes = match source {
+        InputSource::Csv(csv) => return Ok(csv),
+        InputSource::Rows(rows) => return rows_to_csv(&rows),
+        InputSource::XlsxPath { xlsx_path } => {
+            std::fs::read(&xlsx_path).map_err(|e| ScheduleError::invalid(format!("Gagal membaca {}: {}", xlsx_path, e)))?
+        }
+        InputSource::Xlsx { xlsx } => xlsx,
+    };
+
+    xlsx_to_csv(bytes)
+}
+
+fn rows_to_csv<T: Serialize>(rows: &[T]) -> Result<String, ScheduleError> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for row in rows {
+        writer.serialize(row).map_err(|e| format!("Input error: {}", e))?;
+    }
+
+    let csv = writer.into_inner().map_err(|e| format!("Input error: {}", e))?;
+    Ok(String::from_utf8(csv).map_err(|e| format!("Input error: {}", e))?)
+}
+
+// First sheet of a workbook as CSV. Whole-number cells are written without the ".0" Excel
+// stores them with, so they still parse as the integer columns they are.
+fn xlsx_to_csv(bytes: Vec<u8>) -> Result<String, ScheduleError> {
+    let excel_error = |e: &dyn std::fmt::Display| ScheduleError::ParseError {
+        row: None,
+        column: None,
+        message: format!("Excel error: {}", e),
+    };
+
+    let mut workbook = open_workbook_auto_from_rs(std::io::Cursor::new(bytes)).map_err(|e| excel_error(&e))?;
+    let range = workbook
+        .worksheet_range_at(0)
+        .ok_or_else(|| excel_error(&"workbook tidak memiliki sheet"))?
+        .map_err(|e| excel_error(&e))?;
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for row in range.rows() {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|cell| match cell {
+                Data::Empty => String::new(),
+                Data::String(s) => s.trim().to_string(),
+                Data::Float(f) if f.fract() == 0.0 => format!("{}", *f as i64),
+                other => other.to_string(),
+            })
+            .collect();
+        // Trailing blank rows are common in hand-edited sheets
+        if cells.iter().all(|cell| cell.is_empty()) {
+            continue;
+        }
+        writer.write_record(&cells).map_err(|e| format!("Excel error: {}", e))?;
+    }
+
+    let csv = writer.into_inner().map_err(|e| format!("Excel error: {}", e))?;
+    String::from_utf8(csv).map_err(|e| excel_error(&e))
 }
 
-fn parse_preference_csv(csv: &str) -> Result<Vec<TimePreferenceRequest>, String> {
-    let mut rdr = csv::Reader::from_reader(csv.as_bytes());
-    rdr.deserialize()
-        .map(|result| result.map_err(|e| format!("Preference CSV error: {}", e)))
-        .collect()
+pub(crate) fn parse_course_csv(csv: &str) -> Result<Vec<CourseRequest>, ScheduleError> {
+    parse_csv(csv, "Course CSV")
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -103,7 +760,28 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
-        .invoke_handler(tauri::generate_handler![process_pso, stop_pso])
+        .invoke_handler(tauri::generate_handler![
+            process_pso,
+            resume_from_checkpoint,
+            stop_pso,
+            pause_pso,
+            resume_pso,
+            get_status,
+            validate_input,
+            evaluate_schedule,
+            explain_placement,
+            compare_schedules,
+            try_swap,
+            tune_parameters,
+            suggest_parameters,
+            list_runs,
+            get_run,
+            export_schedule_xlsx,
+            export_ics,
+            export_pdf,
+            generate_templates,
+            optimize_batch
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
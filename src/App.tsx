@@ -1,4 +1,4 @@
-import { useEffect, useState } from "react";
+import { useEffect, useRef, useState } from "react";
 import { listen } from '@tauri-apps/api/event';
 import { invoke } from "@tauri-apps/api/core";
 import { save } from '@tauri-apps/plugin-dialog';
@@ -28,6 +28,8 @@ type ScheduleEntry = {
 };
 
 interface OptimizationResult {
+	job_id: number;
+	message?: string;
 	all_best_fitness: number[];
 	conflicts: Record<string, any>; // Sesuaikan kalau tahu strukturnya
 	fitness: number;
@@ -42,6 +44,13 @@ type ElapsedTime = {
 	nanos: number;
 };
 
+// Hasil get_status; result berisi payload "pso-finished" setelah job selesai
+type JobStatus = {
+	job_id: number;
+	state: "queued" | "running" | "paused" | "finished" | "stopped";
+	result: OptimizationResult | null;
+};
+
 export type OptimizationProgress = {
 	all_best_fitness: number | null;
 	best_fitness: number;
@@ -50,6 +59,7 @@ export type OptimizationProgress = {
 	is_finished: boolean;
 	iteration: number;
 	total_runs: number | null;
+	job_id?: number | null;
 };
 
 const App = () => {
@@ -60,6 +70,9 @@ const App = () => {
 	const [isOpen, setIsOpen] = useState(false);
 	const [isRunning, setIsRunning] = useState(false);
 	const [scheduleData, setScheduleData] = useState<ScheduleEntry[]>([]);
+	const jobId = useRef<number | null>(null);
+	// Job yang hasilnya sudah ditampilkan, agar event dan get_status tidak memprosesnya dua kali
+	const handledJob = useRef<number | null>(null);
 	const [params, setParams] = useState({
 		swarm_size: 30,
 		max_iterations: 100,
@@ -85,6 +98,7 @@ const App = () => {
 	useEffect(() => {
  		const unlistenPromise = listen<OptimizationProgress>('optimization-progress', (event) => {
 		const data = event.payload;
+		if (data.job_id != null && data.job_id !== jobId.current) return;
 
 		console.log('Progress:', data);
 
@@ -102,12 +116,31 @@ const App = () => {
 			});
 		});
 
+		const unlistenFinished = listen<OptimizationResult>('pso-finished', (event) => {
+			if (event.payload.job_id !== jobId.current) return;
+			handleFinished(event.payload);
+		});
+
 		return () => {
 			// Pastikan unlisten dipanggil ketika komponen dibersihkan
 			unlistenPromise.then((unlisten) => unlisten());
+			unlistenFinished.then((unlisten) => unlisten());
 		};
 	}, []);
 
+	const handleFinished = (result: OptimizationResult) => {
+		if (handledJob.current === result.job_id) return;
+		handledJob.current = result.job_id;
+
+		if (result.success) {
+			setScheduleData(result.schedule ?? []);
+			showNotification("Optimization completed!", "success");
+		} else {
+			showNotification(`Error: ${result.message}`, "error");
+			setIsRunning(false);
+		}
+	};
+
 	const handleFileChange = async (e: any, setData: any, setFileName: any) => {
 		const file = e.target.files[0];
 		if (!file) return;
@@ -183,7 +216,8 @@ const App = () => {
 
 	const handleStop = async () => {
 		try {
-		await invoke("stop_pso");
+		if (jobId.current === null) return;
+		await invoke("stop_pso", { jobId: jobId.current });
 		console.log("Proses PSO dihentikan.");
 		} catch (error) {
 		console.error("Gagal menghentikan proses:", error);
@@ -215,17 +249,18 @@ const App = () => {
 		try {
 			showNotification("Starting optimization...", "info");
 			setIsRunning(true)
-			const result = await invoke("process_pso", {
+			// Hasil dikirim lewat event "pso-finished"
+			jobId.current = await invoke<number>("process_pso", {
 				courseCsv: courseData,
 				preferenceCsv: preferenceData,
 				params: params,
 			});
-			const typedResult = result as OptimizationResult;
-   			setScheduleData(typedResult.schedule);
-			showNotification("Optimization completed!", "success");
-			// setIsRunning(false)
+
+			// Job kecil bisa selesai sebelum id-nya diketahui, event "pso-finished"-nya sudah terlewat
+			const status = await invoke<JobStatus>("get_status", { jobId: jobId.current });
+			if (status.result) handleFinished(status.result);
 		} catch (err) {
-			showNotification(`Error: ${err}`, "error");
+			showNotification(`Error: ${(err as { message?: string })?.message ?? err}`, "error");
 			setIsRunning(false)
 		}
 	};